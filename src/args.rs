@@ -1,7 +1,62 @@
 use std::{env, path::PathBuf};
 
+use crate::{
+    auth::AuthRule,
+    cors::AllowedOrigin,
+    file_validators::EntityTagMode,
+    http_header::{ContentType, SameSite},
+    http_response::HttpResponseCode,
+    mime::parse_mime_type_mapping,
+    tls::ClientAuth,
+    virtual_host::VirtualHost,
+};
+
 pub struct Args {
     directory: Option<PathBuf>,
+    tls_cert: Option<PathBuf>,
+    tls_key: Option<PathBuf>,
+    tls_client_ca: Option<PathBuf>,
+    tls_client_auth: ClientAuth,
+    tls_client_cert_paths: Vec<String>,
+    tls_client_allowed_names: Vec<String>,
+    htpasswd: Option<PathBuf>,
+    auth_realm: Option<String>,
+    auth_rules: Vec<AuthRule>,
+    bearer_tokens: Vec<String>,
+    jwt_hs256_key: Option<PathBuf>,
+    jwt_rs256_key: Option<PathBuf>,
+    jwt_audience: Option<String>,
+    jwt_issuer: Option<String>,
+    cors_allowed_origins: Vec<AllowedOrigin>,
+    cors_allowed_methods: Vec<String>,
+    cors_allowed_headers: Vec<String>,
+    cors_allow_credentials: bool,
+    cors_max_age: Option<u64>,
+    cors_exposed_headers: Vec<String>,
+    error_pages: Vec<(u16, PathBuf)>,
+    entity_tag_mode: EntityTagMode,
+    mime_types: Vec<(String, ContentType)>,
+    mime_sniff: bool,
+    directory_listing: bool,
+    directory_listing_hidden: bool,
+    spa_fallback: Option<String>,
+    upload_fsync: bool,
+    upload_form: bool,
+    multipart_max_parts: usize,
+    multipart_max_part_size: u64,
+    max_body_size: usize,
+    cookie_domain: Option<String>,
+    cookie_secure: bool,
+    cookie_same_site: SameSite,
+    cookie_partitioned: bool,
+    session_keys: Vec<String>,
+    session_dir: Option<PathBuf>,
+    session_cookie: Option<String>,
+    session_ttl: u64,
+    virtual_hosts: Vec<VirtualHost>,
+    default_host: Option<String>,
+    keep_alive_timeout: u64,
+    max_pipelined_requests: usize,
 }
 
 impl Args {
@@ -17,6 +72,268 @@ impl Args {
 
                     args_builder.with_directory(directory);
                 }
+                "--tls-cert" => {
+                    let tls_cert =
+                        PathBuf::from(args.next().expect("TLS certificate should be given"));
+
+                    args_builder.with_tls_cert(tls_cert);
+                }
+                "--tls-key" => {
+                    let tls_key = PathBuf::from(args.next().expect("TLS key should be given"));
+
+                    args_builder.with_tls_key(tls_key);
+                }
+                "--tls-client-ca" => {
+                    let tls_client_ca =
+                        PathBuf::from(args.next().expect("TLS client CA should be given"));
+
+                    args_builder.with_tls_client_ca(tls_client_ca);
+                }
+                "--tls-client-auth" => {
+                    let tls_client_auth = args.next().expect("TLS client auth should be given");
+                    let tls_client_auth = ClientAuth::try_from(tls_client_auth.as_str())
+                        .expect("TLS client auth should be optional or required");
+
+                    args_builder.with_tls_client_auth(tls_client_auth);
+                }
+                "--tls-client-cert-path" => {
+                    let path = args
+                        .next()
+                        .expect("TLS client certificate path should be given");
+
+                    args_builder.with_tls_client_cert_path(path);
+                }
+                "--tls-client-allow" => {
+                    let name = args.next().expect("TLS client name should be given");
+
+                    args_builder.with_tls_client_allowed_name(name);
+                }
+                "--htpasswd" => {
+                    let htpasswd =
+                        PathBuf::from(args.next().expect("htpasswd file should be given"));
+
+                    args_builder.with_htpasswd(htpasswd);
+                }
+                "--auth-realm" => {
+                    let auth_realm = args.next().expect("Auth realm should be given");
+
+                    args_builder.with_auth_realm(auth_realm);
+                }
+                "--auth-rule" => {
+                    let auth_rule = args.next().expect("Auth rule should be given");
+                    let auth_rule = AuthRule::try_from(auth_rule.as_str())
+                        .expect("Auth rule should be [METHODS:]PATH_PREFIX");
+
+                    args_builder.with_auth_rule(auth_rule);
+                }
+                "--bearer-token" => {
+                    let bearer_token = args.next().expect("Bearer token should be given");
+
+                    args_builder.with_bearer_token(bearer_token);
+                }
+                "--jwt-hs256-key" => {
+                    let jwt_hs256_key =
+                        PathBuf::from(args.next().expect("JWT HS256 key should be given"));
+
+                    args_builder.with_jwt_hs256_key(jwt_hs256_key);
+                }
+                "--jwt-rs256-key" => {
+                    let jwt_rs256_key =
+                        PathBuf::from(args.next().expect("JWT RS256 key should be given"));
+
+                    args_builder.with_jwt_rs256_key(jwt_rs256_key);
+                }
+                "--jwt-audience" => {
+                    let jwt_audience = args.next().expect("JWT audience should be given");
+
+                    args_builder.with_jwt_audience(jwt_audience);
+                }
+                "--jwt-issuer" => {
+                    let jwt_issuer = args.next().expect("JWT issuer should be given");
+
+                    args_builder.with_jwt_issuer(jwt_issuer);
+                }
+                "--cors-origin" => {
+                    let cors_origin = args.next().expect("CORS origin should be given");
+
+                    args_builder
+                        .with_cors_allowed_origin(AllowedOrigin::from(cors_origin.as_str()));
+                }
+                "--cors-methods" => {
+                    let cors_methods = args.next().expect("CORS methods should be given");
+
+                    args_builder.with_cors_allowed_methods(split_list(&cors_methods));
+                }
+                "--cors-headers" => {
+                    let cors_headers = args.next().expect("CORS headers should be given");
+
+                    args_builder.with_cors_allowed_headers(split_list(&cors_headers));
+                }
+                "--cors-credentials" => {
+                    args_builder.with_cors_allow_credentials(true);
+                }
+                "--cors-max-age" => {
+                    let cors_max_age = args
+                        .next()
+                        .expect("CORS max age should be given")
+                        .parse()
+                        .expect("CORS max age should be a number of seconds");
+
+                    args_builder.with_cors_max_age(cors_max_age);
+                }
+                "--cors-expose-headers" => {
+                    let cors_expose_headers =
+                        args.next().expect("CORS exposed headers should be given");
+
+                    args_builder.with_cors_exposed_headers(split_list(&cors_expose_headers));
+                }
+                "--error-page" => {
+                    let error_page = args.next().expect("Error page should be given");
+                    let (code, path) = error_page
+                        .split_once('=')
+                        .expect("Error page should be STATUS=PATH");
+                    let code = code
+                        .parse::<u16>()
+                        .expect("Error page status should be a number");
+                    let code = HttpResponseCode::try_from(code)
+                        .expect("Error page status should be between 100 and 599");
+
+                    args_builder.with_error_page(code.code(), PathBuf::from(path));
+                }
+                "--etag" => {
+                    let entity_tag_mode = args.next().expect("ETag mode should be given");
+                    let entity_tag_mode = EntityTagMode::try_from(entity_tag_mode.as_str())
+                        .expect("ETag mode should be metadata, weak-metadata or content-hash");
+
+                    args_builder.with_entity_tag_mode(entity_tag_mode);
+                }
+                "--mime-type" => {
+                    let mime_type = args.next().expect("MIME type should be given");
+                    let (extension, content_type) = parse_mime_type_mapping(&mime_type)
+                        .expect("MIME type should be EXTENSION=TYPE/SUBTYPE");
+
+                    args_builder.with_mime_type(extension, content_type);
+                }
+                "--mime-sniff" => {
+                    args_builder.with_mime_sniff(true);
+                }
+                "--directory-listing" => {
+                    args_builder.with_directory_listing(true);
+                }
+                "--directory-listing-hidden" => {
+                    args_builder.with_directory_listing_hidden(true);
+                }
+                "--spa-fallback" => {
+                    let spa_fallback = args.next().expect("SPA fallback file should be given");
+
+                    args_builder.with_spa_fallback(spa_fallback);
+                }
+                "--upload-fsync" => {
+                    args_builder.with_upload_fsync(true);
+                }
+                "--upload-form" => {
+                    args_builder.with_upload_form(true);
+                }
+                "--multipart-max-parts" => {
+                    let multipart_max_parts = args
+                        .next()
+                        .expect("Multipart max parts should be given")
+                        .parse()
+                        .expect("Multipart max parts should be a number");
+
+                    args_builder.with_multipart_max_parts(multipart_max_parts);
+                }
+                "--multipart-max-part-size" => {
+                    let multipart_max_part_size = args
+                        .next()
+                        .expect("Multipart max part size should be given")
+                        .parse()
+                        .expect("Multipart max part size should be a number of bytes");
+
+                    args_builder.with_multipart_max_part_size(multipart_max_part_size);
+                }
+                "--max-body-size" => {
+                    let max_body_size = args
+                        .next()
+                        .expect("Max body size should be given")
+                        .parse()
+                        .expect("Max body size should be a number of bytes");
+
+                    args_builder.with_max_body_size(max_body_size);
+                }
+                "--cookie-domain" => {
+                    let cookie_domain = args.next().expect("Cookie domain should be given");
+
+                    args_builder.with_cookie_domain(cookie_domain);
+                }
+                "--cookie-secure" => {
+                    args_builder.with_cookie_secure(true);
+                }
+                "--cookie-same-site" => {
+                    let cookie_same_site = args.next().expect("Cookie SameSite should be given");
+                    let cookie_same_site = SameSite::try_from(cookie_same_site.as_str())
+                        .expect("Cookie SameSite should be strict, lax or none");
+
+                    args_builder.with_cookie_same_site(cookie_same_site);
+                }
+                "--cookie-partitioned" => {
+                    args_builder.with_cookie_partitioned(true);
+                }
+                "--session-key" => {
+                    let session_key = args.next().expect("Session key should be given");
+
+                    args_builder.with_session_key(session_key);
+                }
+                "--session-dir" => {
+                    let session_dir =
+                        PathBuf::from(args.next().expect("Session directory should be given"));
+
+                    args_builder.with_session_dir(session_dir);
+                }
+                "--session-cookie" => {
+                    let session_cookie = args.next().expect("Session cookie name should be given");
+
+                    args_builder.with_session_cookie(session_cookie);
+                }
+                "--session-ttl" => {
+                    let session_ttl = args
+                        .next()
+                        .expect("Session TTL should be given")
+                        .parse()
+                        .expect("Session TTL should be a number of seconds");
+
+                    args_builder.with_session_ttl(session_ttl);
+                }
+                "--vhost" => {
+                    let virtual_host = args.next().expect("Virtual host should be given");
+                    let virtual_host = VirtualHost::try_from(virtual_host.as_str())
+                        .expect("Virtual host should be HOST=DIR[;OPTION=VALUE...]");
+
+                    args_builder.with_virtual_host(virtual_host);
+                }
+                "--default-host" => {
+                    let default_host = args.next().expect("Default host should be given");
+
+                    args_builder.with_default_host(default_host);
+                }
+                "--keep-alive-timeout" => {
+                    let keep_alive_timeout = args
+                        .next()
+                        .expect("Keep-alive timeout should be given")
+                        .parse()
+                        .expect("Keep-alive timeout should be a number of seconds");
+
+                    args_builder.with_keep_alive_timeout(keep_alive_timeout);
+                }
+                "--max-pipelined-requests" => {
+                    let max_pipelined_requests = args
+                        .next()
+                        .expect("Max pipelined requests should be given")
+                        .parse()
+                        .expect("Max pipelined requests should be a number");
+
+                    args_builder.with_max_pipelined_requests(max_pipelined_requests);
+                }
                 _ => {
                     panic!("Unknown argument");
                 }
@@ -30,18 +347,280 @@ impl Args {
         self.directory.take()
     }
 
-    fn new(directory: Option<PathBuf>) -> Self {
-        Self { directory }
+    pub fn take_tls_cert(&mut self) -> Option<PathBuf> {
+        self.tls_cert.take()
+    }
+
+    pub fn take_tls_key(&mut self) -> Option<PathBuf> {
+        self.tls_key.take()
+    }
+
+    pub fn take_tls_client_ca(&mut self) -> Option<PathBuf> {
+        self.tls_client_ca.take()
+    }
+
+    pub fn tls_client_auth(&self) -> ClientAuth {
+        self.tls_client_auth
+    }
+
+    pub fn take_tls_client_cert_paths(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.tls_client_cert_paths)
+    }
+
+    pub fn take_tls_client_allowed_names(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.tls_client_allowed_names)
+    }
+
+    pub fn take_htpasswd(&mut self) -> Option<PathBuf> {
+        self.htpasswd.take()
+    }
+
+    pub fn take_auth_realm(&mut self) -> Option<String> {
+        self.auth_realm.take()
+    }
+
+    pub fn take_auth_rules(&mut self) -> Vec<AuthRule> {
+        std::mem::take(&mut self.auth_rules)
+    }
+
+    pub fn take_bearer_tokens(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.bearer_tokens)
+    }
+
+    pub fn take_jwt_hs256_key(&mut self) -> Option<PathBuf> {
+        self.jwt_hs256_key.take()
+    }
+
+    pub fn take_jwt_rs256_key(&mut self) -> Option<PathBuf> {
+        self.jwt_rs256_key.take()
+    }
+
+    pub fn take_jwt_audience(&mut self) -> Option<String> {
+        self.jwt_audience.take()
+    }
+
+    pub fn take_jwt_issuer(&mut self) -> Option<String> {
+        self.jwt_issuer.take()
+    }
+
+    pub fn take_cors_allowed_origins(&mut self) -> Vec<AllowedOrigin> {
+        std::mem::take(&mut self.cors_allowed_origins)
+    }
+
+    pub fn take_cors_allowed_methods(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.cors_allowed_methods)
+    }
+
+    pub fn take_cors_allowed_headers(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.cors_allowed_headers)
+    }
+
+    pub fn cors_allow_credentials(&self) -> bool {
+        self.cors_allow_credentials
+    }
+
+    pub fn cors_max_age(&self) -> Option<u64> {
+        self.cors_max_age
+    }
+
+    pub fn take_cors_exposed_headers(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.cors_exposed_headers)
+    }
+
+    pub fn take_error_pages(&mut self) -> Vec<(u16, PathBuf)> {
+        std::mem::take(&mut self.error_pages)
+    }
+
+    pub fn entity_tag_mode(&self) -> EntityTagMode {
+        self.entity_tag_mode
+    }
+
+    pub fn take_mime_types(&mut self) -> Vec<(String, ContentType)> {
+        std::mem::take(&mut self.mime_types)
+    }
+
+    pub fn mime_sniff(&self) -> bool {
+        self.mime_sniff
+    }
+
+    pub fn directory_listing(&self) -> bool {
+        self.directory_listing
+    }
+
+    pub fn directory_listing_hidden(&self) -> bool {
+        self.directory_listing_hidden
+    }
+
+    pub fn take_spa_fallback(&mut self) -> Option<String> {
+        self.spa_fallback.take()
+    }
+
+    pub fn upload_fsync(&self) -> bool {
+        self.upload_fsync
+    }
+
+    pub fn upload_form(&self) -> bool {
+        self.upload_form
+    }
+
+    pub fn multipart_max_parts(&self) -> usize {
+        self.multipart_max_parts
+    }
+
+    pub fn multipart_max_part_size(&self) -> u64 {
+        self.multipart_max_part_size
+    }
+
+    pub fn max_body_size(&self) -> usize {
+        self.max_body_size
+    }
+
+    pub fn take_cookie_domain(&mut self) -> Option<String> {
+        self.cookie_domain.take()
+    }
+
+    pub fn cookie_secure(&self) -> bool {
+        self.cookie_secure
+    }
+
+    pub fn cookie_same_site(&self) -> SameSite {
+        self.cookie_same_site
+    }
+
+    pub fn cookie_partitioned(&self) -> bool {
+        self.cookie_partitioned
+    }
+
+    pub fn take_session_keys(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.session_keys)
+    }
+
+    pub fn take_session_dir(&mut self) -> Option<PathBuf> {
+        self.session_dir.take()
+    }
+
+    pub fn take_session_cookie(&mut self) -> Option<String> {
+        self.session_cookie.take()
+    }
+
+    pub fn session_ttl(&self) -> u64 {
+        self.session_ttl
+    }
+
+    pub fn take_virtual_hosts(&mut self) -> Vec<VirtualHost> {
+        std::mem::take(&mut self.virtual_hosts)
+    }
+
+    pub fn take_default_host(&mut self) -> Option<String> {
+        self.default_host.take()
+    }
+
+    pub fn keep_alive_timeout(&self) -> u64 {
+        self.keep_alive_timeout
+    }
+
+    pub fn max_pipelined_requests(&self) -> usize {
+        self.max_pipelined_requests
     }
 }
 
 struct ArgsBuilder {
     directory: Option<PathBuf>,
+    tls_cert: Option<PathBuf>,
+    tls_key: Option<PathBuf>,
+    tls_client_ca: Option<PathBuf>,
+    tls_client_auth: ClientAuth,
+    tls_client_cert_paths: Vec<String>,
+    tls_client_allowed_names: Vec<String>,
+    htpasswd: Option<PathBuf>,
+    auth_realm: Option<String>,
+    auth_rules: Vec<AuthRule>,
+    bearer_tokens: Vec<String>,
+    jwt_hs256_key: Option<PathBuf>,
+    jwt_rs256_key: Option<PathBuf>,
+    jwt_audience: Option<String>,
+    jwt_issuer: Option<String>,
+    cors_allowed_origins: Vec<AllowedOrigin>,
+    cors_allowed_methods: Vec<String>,
+    cors_allowed_headers: Vec<String>,
+    cors_allow_credentials: bool,
+    cors_max_age: Option<u64>,
+    cors_exposed_headers: Vec<String>,
+    error_pages: Vec<(u16, PathBuf)>,
+    entity_tag_mode: EntityTagMode,
+    mime_types: Vec<(String, ContentType)>,
+    mime_sniff: bool,
+    directory_listing: bool,
+    directory_listing_hidden: bool,
+    spa_fallback: Option<String>,
+    upload_fsync: bool,
+    upload_form: bool,
+    multipart_max_parts: usize,
+    multipart_max_part_size: u64,
+    max_body_size: usize,
+    cookie_domain: Option<String>,
+    cookie_secure: bool,
+    cookie_same_site: SameSite,
+    cookie_partitioned: bool,
+    session_keys: Vec<String>,
+    session_dir: Option<PathBuf>,
+    session_cookie: Option<String>,
+    session_ttl: u64,
+    virtual_hosts: Vec<VirtualHost>,
+    default_host: Option<String>,
+    keep_alive_timeout: u64,
+    max_pipelined_requests: usize,
 }
 
 impl ArgsBuilder {
     fn new() -> Self {
-        Self { directory: None }
+        Self {
+            directory: None,
+            tls_cert: None,
+            tls_key: None,
+            tls_client_ca: None,
+            tls_client_auth: ClientAuth::Optional,
+            tls_client_cert_paths: vec![],
+            tls_client_allowed_names: vec![],
+            htpasswd: None,
+            auth_realm: None,
+            auth_rules: vec![],
+            bearer_tokens: vec![],
+            jwt_hs256_key: None,
+            jwt_rs256_key: None,
+            jwt_audience: None,
+            jwt_issuer: None,
+            cors_allowed_origins: vec![],
+            cors_allowed_methods: split_list("GET, POST, PUT, OPTIONS"),
+            cors_allowed_headers: vec![],
+            cors_allow_credentials: false,
+            cors_max_age: None,
+            cors_exposed_headers: vec![],
+            error_pages: vec![],
+            entity_tag_mode: EntityTagMode::Metadata,
+            mime_types: vec![],
+            mime_sniff: false,
+            directory_listing: false,
+            directory_listing_hidden: false,
+            spa_fallback: None,
+            upload_fsync: false,
+            upload_form: false,
+            multipart_max_parts: 32,
+            multipart_max_part_size: 1024 * 1024 * 1024,
+            max_body_size: 1024 * 1024,
+            cookie_domain: None,
+            cookie_secure: false,
+            cookie_same_site: SameSite::Lax,
+            cookie_partitioned: false,
+            session_keys: vec![],
+            session_dir: None,
+            session_cookie: None,
+            session_ttl: 24 * 60 * 60,
+            virtual_hosts: vec![],
+            default_host: None,
+            keep_alive_timeout: 5,
+            max_pipelined_requests: 16,
+        }
     }
 
     fn with_directory(&mut self, directory: PathBuf) -> &mut Self {
@@ -50,7 +629,326 @@ impl ArgsBuilder {
         self
     }
 
+    fn with_tls_cert(&mut self, tls_cert: PathBuf) -> &mut Self {
+        self.tls_cert = Some(tls_cert);
+
+        self
+    }
+
+    fn with_tls_key(&mut self, tls_key: PathBuf) -> &mut Self {
+        self.tls_key = Some(tls_key);
+
+        self
+    }
+
+    fn with_tls_client_ca(&mut self, tls_client_ca: PathBuf) -> &mut Self {
+        self.tls_client_ca = Some(tls_client_ca);
+
+        self
+    }
+
+    fn with_tls_client_auth(&mut self, tls_client_auth: ClientAuth) -> &mut Self {
+        self.tls_client_auth = tls_client_auth;
+
+        self
+    }
+
+    fn with_tls_client_cert_path(&mut self, path: String) -> &mut Self {
+        self.tls_client_cert_paths.push(path);
+
+        self
+    }
+
+    fn with_tls_client_allowed_name(&mut self, name: String) -> &mut Self {
+        self.tls_client_allowed_names.push(name);
+
+        self
+    }
+
+    fn with_htpasswd(&mut self, htpasswd: PathBuf) -> &mut Self {
+        self.htpasswd = Some(htpasswd);
+
+        self
+    }
+
+    fn with_auth_realm(&mut self, auth_realm: String) -> &mut Self {
+        self.auth_realm = Some(auth_realm);
+
+        self
+    }
+
+    fn with_auth_rule(&mut self, auth_rule: AuthRule) -> &mut Self {
+        self.auth_rules.push(auth_rule);
+
+        self
+    }
+
+    fn with_bearer_token(&mut self, bearer_token: String) -> &mut Self {
+        self.bearer_tokens.push(bearer_token);
+
+        self
+    }
+
+    fn with_jwt_hs256_key(&mut self, jwt_hs256_key: PathBuf) -> &mut Self {
+        self.jwt_hs256_key = Some(jwt_hs256_key);
+
+        self
+    }
+
+    fn with_jwt_rs256_key(&mut self, jwt_rs256_key: PathBuf) -> &mut Self {
+        self.jwt_rs256_key = Some(jwt_rs256_key);
+
+        self
+    }
+
+    fn with_jwt_audience(&mut self, jwt_audience: String) -> &mut Self {
+        self.jwt_audience = Some(jwt_audience);
+
+        self
+    }
+
+    fn with_jwt_issuer(&mut self, jwt_issuer: String) -> &mut Self {
+        self.jwt_issuer = Some(jwt_issuer);
+
+        self
+    }
+
+    fn with_cors_allowed_origin(&mut self, cors_allowed_origin: AllowedOrigin) -> &mut Self {
+        self.cors_allowed_origins.push(cors_allowed_origin);
+
+        self
+    }
+
+    fn with_cors_allowed_methods(&mut self, cors_allowed_methods: Vec<String>) -> &mut Self {
+        self.cors_allowed_methods = cors_allowed_methods;
+
+        self
+    }
+
+    fn with_cors_allowed_headers(&mut self, cors_allowed_headers: Vec<String>) -> &mut Self {
+        self.cors_allowed_headers = cors_allowed_headers;
+
+        self
+    }
+
+    fn with_cors_allow_credentials(&mut self, cors_allow_credentials: bool) -> &mut Self {
+        self.cors_allow_credentials = cors_allow_credentials;
+
+        self
+    }
+
+    fn with_cors_max_age(&mut self, cors_max_age: u64) -> &mut Self {
+        self.cors_max_age = Some(cors_max_age);
+
+        self
+    }
+
+    fn with_cors_exposed_headers(&mut self, cors_exposed_headers: Vec<String>) -> &mut Self {
+        self.cors_exposed_headers = cors_exposed_headers;
+
+        self
+    }
+
+    fn with_error_page(&mut self, code: u16, path: PathBuf) -> &mut Self {
+        self.error_pages.push((code, path));
+
+        self
+    }
+
+    fn with_entity_tag_mode(&mut self, entity_tag_mode: EntityTagMode) -> &mut Self {
+        self.entity_tag_mode = entity_tag_mode;
+
+        self
+    }
+
+    fn with_mime_type(&mut self, extension: String, content_type: ContentType) -> &mut Self {
+        self.mime_types.push((extension, content_type));
+
+        self
+    }
+
+    fn with_mime_sniff(&mut self, mime_sniff: bool) -> &mut Self {
+        self.mime_sniff = mime_sniff;
+
+        self
+    }
+
+    fn with_directory_listing(&mut self, directory_listing: bool) -> &mut Self {
+        self.directory_listing = directory_listing;
+
+        self
+    }
+
+    fn with_directory_listing_hidden(&mut self, directory_listing_hidden: bool) -> &mut Self {
+        self.directory_listing_hidden = directory_listing_hidden;
+
+        self
+    }
+
+    fn with_spa_fallback(&mut self, spa_fallback: String) -> &mut Self {
+        self.spa_fallback = Some(spa_fallback);
+
+        self
+    }
+
+    fn with_upload_fsync(&mut self, upload_fsync: bool) -> &mut Self {
+        self.upload_fsync = upload_fsync;
+
+        self
+    }
+
+    fn with_upload_form(&mut self, upload_form: bool) -> &mut Self {
+        self.upload_form = upload_form;
+
+        self
+    }
+
+    fn with_multipart_max_parts(&mut self, multipart_max_parts: usize) -> &mut Self {
+        self.multipart_max_parts = multipart_max_parts;
+
+        self
+    }
+
+    fn with_multipart_max_part_size(&mut self, multipart_max_part_size: u64) -> &mut Self {
+        self.multipart_max_part_size = multipart_max_part_size;
+
+        self
+    }
+
+    fn with_max_body_size(&mut self, max_body_size: usize) -> &mut Self {
+        self.max_body_size = max_body_size;
+
+        self
+    }
+
+    fn with_cookie_domain(&mut self, cookie_domain: String) -> &mut Self {
+        self.cookie_domain = Some(cookie_domain);
+
+        self
+    }
+
+    fn with_cookie_secure(&mut self, cookie_secure: bool) -> &mut Self {
+        self.cookie_secure = cookie_secure;
+
+        self
+    }
+
+    fn with_cookie_same_site(&mut self, cookie_same_site: SameSite) -> &mut Self {
+        self.cookie_same_site = cookie_same_site;
+
+        self
+    }
+
+    fn with_cookie_partitioned(&mut self, cookie_partitioned: bool) -> &mut Self {
+        self.cookie_partitioned = cookie_partitioned;
+
+        self
+    }
+
+    fn with_session_key(&mut self, session_key: String) -> &mut Self {
+        self.session_keys.push(session_key);
+
+        self
+    }
+
+    fn with_session_dir(&mut self, session_dir: PathBuf) -> &mut Self {
+        self.session_dir = Some(session_dir);
+
+        self
+    }
+
+    fn with_session_cookie(&mut self, session_cookie: String) -> &mut Self {
+        self.session_cookie = Some(session_cookie);
+
+        self
+    }
+
+    fn with_session_ttl(&mut self, session_ttl: u64) -> &mut Self {
+        self.session_ttl = session_ttl;
+
+        self
+    }
+
+    fn with_virtual_host(&mut self, virtual_host: VirtualHost) -> &mut Self {
+        self.virtual_hosts.push(virtual_host);
+
+        self
+    }
+
+    fn with_default_host(&mut self, default_host: String) -> &mut Self {
+        self.default_host = Some(default_host);
+
+        self
+    }
+
+    fn with_keep_alive_timeout(&mut self, keep_alive_timeout: u64) -> &mut Self {
+        self.keep_alive_timeout = keep_alive_timeout;
+
+        self
+    }
+
+    fn with_max_pipelined_requests(&mut self, max_pipelined_requests: usize) -> &mut Self {
+        self.max_pipelined_requests = max_pipelined_requests;
+
+        self
+    }
+
     fn build(self) -> Args {
-        Args::new(self.directory)
+        Args {
+            directory: self.directory,
+            tls_cert: self.tls_cert,
+            tls_key: self.tls_key,
+            tls_client_ca: self.tls_client_ca,
+            tls_client_auth: self.tls_client_auth,
+            tls_client_cert_paths: self.tls_client_cert_paths,
+            tls_client_allowed_names: self.tls_client_allowed_names,
+            htpasswd: self.htpasswd,
+            auth_realm: self.auth_realm,
+            auth_rules: self.auth_rules,
+            bearer_tokens: self.bearer_tokens,
+            jwt_hs256_key: self.jwt_hs256_key,
+            jwt_rs256_key: self.jwt_rs256_key,
+            jwt_audience: self.jwt_audience,
+            jwt_issuer: self.jwt_issuer,
+            cors_allowed_origins: self.cors_allowed_origins,
+            cors_allowed_methods: self.cors_allowed_methods,
+            cors_allowed_headers: self.cors_allowed_headers,
+            cors_allow_credentials: self.cors_allow_credentials,
+            cors_max_age: self.cors_max_age,
+            cors_exposed_headers: self.cors_exposed_headers,
+            error_pages: self.error_pages,
+            entity_tag_mode: self.entity_tag_mode,
+            mime_types: self.mime_types,
+            mime_sniff: self.mime_sniff,
+            directory_listing: self.directory_listing,
+            directory_listing_hidden: self.directory_listing_hidden,
+            spa_fallback: self.spa_fallback,
+            upload_fsync: self.upload_fsync,
+            upload_form: self.upload_form,
+            multipart_max_parts: self.multipart_max_parts,
+            multipart_max_part_size: self.multipart_max_part_size,
+            max_body_size: self.max_body_size,
+            cookie_domain: self.cookie_domain,
+            cookie_secure: self.cookie_secure,
+            cookie_same_site: self.cookie_same_site,
+            cookie_partitioned: self.cookie_partitioned,
+            session_keys: self.session_keys,
+            session_dir: self.session_dir,
+            session_cookie: self.session_cookie,
+            session_ttl: self.session_ttl,
+            virtual_hosts: self.virtual_hosts,
+            default_host: self.default_host,
+            keep_alive_timeout: self.keep_alive_timeout,
+            max_pipelined_requests: self.max_pipelined_requests,
+        }
     }
 }
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
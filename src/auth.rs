@@ -0,0 +1,282 @@
+use std::{error::Error, fmt::Display};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{
+    htpasswd::HtpasswdFile,
+    http_header::{Authorization, WwwAuthenticate},
+    http_request::{HttpMethod, HttpRequest},
+    jwt::{Claims, JwtValidator},
+    request_target::has_path_prefix,
+    session::Session,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub enum Principal {
+    User(String),
+    Token,
+    Jwt(Claims),
+}
+
+pub struct AuthRule {
+    http_methods: Option<Vec<HttpMethod>>,
+    path_prefix: String,
+}
+
+impl AuthRule {
+    fn applies_to(&self, http_method: &HttpMethod, request_target: &str) -> bool {
+        let method_matches = match &self.http_methods {
+            Some(http_methods) => http_methods.contains(http_method),
+            None => true,
+        };
+
+        method_matches && has_path_prefix(request_target, &self.path_prefix)
+    }
+}
+
+impl TryFrom<&str> for AuthRule {
+    type Error = AuthRuleError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.split_once(':') {
+            Some((http_methods, path_prefix)) if !http_methods.starts_with('/') => {
+                let http_methods = http_methods
+                    .split(',')
+                    .map(HttpMethod::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| AuthRuleError::new(value))?;
+
+                Ok(Self {
+                    http_methods: Some(http_methods),
+                    path_prefix: path_prefix.to_string(),
+                })
+            }
+            _ if value.starts_with('/') => Ok(Self {
+                http_methods: None,
+                path_prefix: value.to_string(),
+            }),
+            _ => Err(AuthRuleError::new(value)),
+        }
+    }
+}
+
+pub struct Authenticator {
+    realm: String,
+    auth_rules: Vec<AuthRule>,
+    htpasswd_file: Option<HtpasswdFile>,
+    bearer_token_key: [u8; 32],
+    bearer_token_macs: Vec<Vec<u8>>,
+    jwt_validator: Option<JwtValidator>,
+}
+
+impl Authenticator {
+    pub fn new(
+        realm: String,
+        auth_rules: Vec<AuthRule>,
+        htpasswd_file: Option<HtpasswdFile>,
+        bearer_tokens: Vec<String>,
+        jwt_validator: Option<JwtValidator>,
+    ) -> Self {
+        let bearer_token_key = rand::random::<[u8; 32]>();
+        let bearer_token_macs = bearer_tokens
+            .iter()
+            .map(|bearer_token| {
+                bearer_token_mac(&bearer_token_key, bearer_token)
+                    .finalize()
+                    .into_bytes()
+                    .to_vec()
+            })
+            .collect();
+
+        Self {
+            realm,
+            auth_rules,
+            htpasswd_file,
+            bearer_token_key,
+            bearer_token_macs,
+            jwt_validator,
+        }
+    }
+
+    fn is_bearer_token(&self, token: &str) -> bool {
+        let mac = bearer_token_mac(&self.bearer_token_key, token);
+
+        self.bearer_token_macs
+            .iter()
+            .any(|bearer_token_mac| mac.clone().verify_slice(bearer_token_mac).is_ok())
+    }
+
+    fn requires_authentication(&self, http_method: &HttpMethod, request_target: &str) -> bool {
+        self.auth_rules
+            .iter()
+            .any(|auth_rule| auth_rule.applies_to(http_method, request_target))
+    }
+
+    pub async fn authenticate(
+        &self,
+        http_request: &mut HttpRequest,
+        session: Option<&Session>,
+    ) -> bool {
+        let principal = match http_request.authorization() {
+            Some(authorization) => self.principal(authorization).await,
+            None => session
+                .and_then(Session::user)
+                .map(|user_id| Principal::User(user_id.to_string())),
+        };
+        let requires_authentication = !http_request.is_cors_preflight()
+            && self
+                .requires_authentication(http_request.http_method(), &http_request.decoded_path());
+        let authenticated = principal.is_some() || !requires_authentication;
+        if let Some(principal) = principal {
+            http_request.set_principal(principal);
+        }
+
+        authenticated
+    }
+
+    async fn principal(&self, authorization: &Authorization) -> Option<Principal> {
+        match authorization {
+            Authorization::Basic(basic_credentials) => {
+                let htpasswd_file = self.htpasswd_file.as_ref()?;
+                let verified = htpasswd_file
+                    .verify(basic_credentials.user_id(), basic_credentials.password())
+                    .await;
+
+                verified.then(|| Principal::User(basic_credentials.user_id().to_string()))
+            }
+            Authorization::Bearer(token) => {
+                if self.is_bearer_token(token) {
+                    return Some(Principal::Token);
+                }
+
+                let claims = self.jwt_validator.as_ref()?.validate(token)?;
+
+                Some(Principal::Jwt(claims))
+            }
+            Authorization::Other(_) => None,
+        }
+    }
+
+    pub fn authorizes(&self, http_request: &HttpRequest, path: &str) -> bool {
+        http_request.is_authenticated()
+            || !self.requires_authentication(http_request.http_method(), path)
+    }
+
+    pub fn challenges(&self) -> Vec<WwwAuthenticate> {
+        let mut challenges = vec![];
+        if self.htpasswd_file.is_some() {
+            challenges.push(WwwAuthenticate::new("Basic", &self.realm));
+        }
+        if !self.bearer_token_macs.is_empty() || self.jwt_validator.is_some() {
+            challenges.push(WwwAuthenticate::new("Bearer", &self.realm));
+        }
+        if challenges.is_empty() {
+            challenges.push(WwwAuthenticate::new("Basic", &self.realm));
+        }
+
+        challenges
+    }
+}
+
+fn bearer_token_mac(key: &[u8], bearer_token: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key size");
+    mac.update(bearer_token.as_bytes());
+
+    mac
+}
+
+#[derive(Debug)]
+pub struct AuthRuleError {
+    auth_rule: String,
+}
+
+impl AuthRuleError {
+    fn new(auth_rule: &str) -> Self {
+        Self {
+            auth_rule: auth_rule.to_string(),
+        }
+    }
+}
+
+impl Display for AuthRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid auth rule: {}", self.auth_rule)
+    }
+}
+
+impl Error for AuthRuleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_request::HttpRequestError;
+
+    async fn parse_request(raw: &str) -> Option<HttpRequest> {
+        HttpRequest::from_reader(&mut raw.as_bytes(), None)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    async fn authenticate(raw: &str) -> Option<bool> {
+        let authenticator = Authenticator::new(
+            String::from("test"),
+            vec![AuthRule::try_from("/files/private").unwrap()],
+            None,
+            vec![String::from("secret")],
+            None,
+        );
+        let mut http_request = parse_request(raw).await?;
+
+        Some(authenticator.authenticate(&mut http_request, None).await)
+    }
+
+    #[tokio::test]
+    async fn auth_rule_protects_its_prefix() {
+        assert_eq!(
+            authenticate("GET /files/private/s.txt HTTP/1.1\r\nHost: x\r\n\r\n").await,
+            Some(false)
+        );
+        assert_eq!(
+            authenticate(
+                "GET /files/private/s.txt HTTP/1.1\r\nHost: x\r\nAuthorization: Bearer secret\r\n\r\n"
+            )
+            .await,
+            Some(true)
+        );
+        assert_eq!(
+            authenticate("GET /files/private2/s.txt HTTP/1.1\r\nHost: x\r\n\r\n").await,
+            Some(true)
+        );
+    }
+
+    #[tokio::test]
+    async fn auth_rule_applies_to_dot_segments() {
+        assert_eq!(
+            authenticate("GET /files/./private/s.txt HTTP/1.1\r\nHost: x\r\n\r\n").await,
+            Some(false)
+        );
+    }
+
+    #[tokio::test]
+    async fn auth_rule_applies_to_empty_segments() {
+        assert_eq!(
+            authenticate("GET /files//private/s.txt HTTP/1.1\r\nHost: x\r\n\r\n").await,
+            Some(false)
+        );
+    }
+
+    #[tokio::test]
+    async fn encoded_separators_cannot_skip_auth_rules() {
+        for raw in [
+            "GET /files/%2Fprivate/s.txt HTTP/1.1\r\nHost: x\r\n\r\n",
+            "POST /files/.%2Fprivate%2Fw.txt HTTP/1.1\r\nHost: x\r\nContent-Length: 0\r\n\r\n",
+        ] {
+            let parsed = HttpRequest::from_reader(&mut raw.as_bytes(), None).await;
+
+            assert!(matches!(parsed, Err(HttpRequestError::Malformed(_))));
+        }
+    }
+}
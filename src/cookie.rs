@@ -0,0 +1,49 @@
+use std::time::UNIX_EPOCH;
+
+use crate::http_header::{CookieError, HttpDate, SameSite, SetCookie};
+
+pub struct CookieOptions {
+    domain: Option<String>,
+    secure: bool,
+    same_site: SameSite,
+    partitioned: bool,
+}
+
+impl CookieOptions {
+    pub fn new(
+        domain: Option<String>,
+        secure: bool,
+        same_site: SameSite,
+        partitioned: bool,
+    ) -> Self {
+        Self {
+            domain,
+            secure,
+            same_site,
+            partitioned,
+        }
+    }
+
+    pub fn set_cookie(&self, name: &str, value: &str) -> Result<SetCookie, CookieError> {
+        let mut set_cookie = SetCookie::try_new(name, value)?
+            .with_path("/")?
+            .with_secure(self.secure)
+            .with_http_only(true)
+            .with_same_site(self.same_site)
+            .with_partitioned(self.partitioned);
+        if let Some(domain) = &self.domain {
+            set_cookie = set_cookie.with_domain(domain)?;
+        }
+
+        Ok(set_cookie)
+    }
+
+    pub fn expire_cookie(&self, name: &str) -> Result<SetCookie, CookieError> {
+        let set_cookie = self
+            .set_cookie(name, "")?
+            .with_expires(HttpDate::from(UNIX_EPOCH))
+            .with_max_age(0);
+
+        Ok(set_cookie)
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    http_header::{AccessControlAllowOrigin, AccessControlMaxAge, HttpHeader, TokenList},
+    http_request::HttpRequest,
+    http_response::HttpResponse,
+};
+
+pub enum AllowedOrigin {
+    Any,
+    Exact(String),
+    Pattern(String),
+}
+
+impl AllowedOrigin {
+    fn matches(&self, origin: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Exact(allowed_origin) => allowed_origin.eq_ignore_ascii_case(origin),
+            Self::Pattern(pattern) => glob_matches(pattern, origin),
+        }
+    }
+}
+
+impl From<&str> for AllowedOrigin {
+    fn from(value: &str) -> Self {
+        if value == "*" {
+            Self::Any
+        } else if value.contains('*') {
+            Self::Pattern(value.to_string())
+        } else {
+            Self::Exact(value.to_string())
+        }
+    }
+}
+
+pub struct CorsPolicy {
+    allowed_origins: Vec<AllowedOrigin>,
+    allowed_methods: Vec<String>,
+    allowed_headers: Vec<String>,
+    allow_credentials: bool,
+    max_age: Option<u64>,
+    exposed_headers: Vec<String>,
+}
+
+impl CorsPolicy {
+    pub fn new(
+        allowed_origins: Vec<AllowedOrigin>,
+        allowed_methods: Vec<String>,
+        allowed_headers: Vec<String>,
+        allow_credentials: bool,
+        max_age: Option<u64>,
+        exposed_headers: Vec<String>,
+    ) -> Self {
+        Self {
+            allowed_origins,
+            allowed_methods,
+            allowed_headers,
+            allow_credentials,
+            max_age,
+            exposed_headers,
+        }
+    }
+
+    pub fn preflight_headers(&self, http_request: &HttpRequest) -> Vec<HttpHeader> {
+        let mut http_headers = self.vary_headers(&[
+            "Origin",
+            "Access-Control-Request-Method",
+            "Access-Control-Request-Headers",
+        ]);
+        let Some(allow_origin) = self.allow_origin(http_request) else {
+            return http_headers;
+        };
+        let request_method_allowed =
+            http_request
+                .access_control_request_method()
+                .is_some_and(|request_method| {
+                    self.allowed_methods
+                        .iter()
+                        .any(|allowed_method| allowed_method == request_method.value())
+                });
+        if !request_method_allowed {
+            return http_headers;
+        }
+
+        let allowed_headers = if self.allowed_headers.is_empty() {
+            http_request
+                .access_control_request_headers()
+                .map_or(vec![], |request_headers| request_headers.tokens().to_vec())
+        } else {
+            self.allowed_headers.clone()
+        };
+
+        http_headers.push(allow_origin);
+        http_headers.push(HttpHeader::AccessControlAllowMethods(TokenList::new(
+            self.allowed_methods.clone(),
+        )));
+        if !allowed_headers.is_empty() {
+            http_headers.push(HttpHeader::AccessControlAllowHeaders(TokenList::new(
+                allowed_headers,
+            )));
+        }
+        if self.allow_credentials {
+            http_headers.push(HttpHeader::AccessControlAllowCredentials);
+        }
+        if let Some(max_age) = self.max_age {
+            http_headers.push(HttpHeader::AccessControlMaxAge(AccessControlMaxAge::new(
+                max_age,
+            )));
+        }
+
+        http_headers
+    }
+
+    pub fn apply(&self, http_request: &HttpRequest, http_response: &mut HttpResponse) {
+        for http_header in self.vary_headers(&["Origin"]) {
+            http_response.add_header(http_header);
+        }
+        let Some(allow_origin) = self.allow_origin(http_request) else {
+            return;
+        };
+
+        http_response.add_header(allow_origin);
+        if self.allow_credentials {
+            http_response.add_header(HttpHeader::AccessControlAllowCredentials);
+        }
+        if !self.exposed_headers.is_empty() {
+            http_response.add_header(HttpHeader::AccessControlExposeHeaders(TokenList::new(
+                self.exposed_headers.clone(),
+            )));
+        }
+    }
+
+    fn allow_origin(&self, http_request: &HttpRequest) -> Option<HttpHeader> {
+        let origin = http_request.origin()?.value();
+        let allowed_origin = self
+            .allowed_origins
+            .iter()
+            .find(|allowed_origin| allowed_origin.matches(origin))?;
+        let allow_origin = match allowed_origin {
+            AllowedOrigin::Any if !self.allow_credentials => String::from("*"),
+            _ => origin.to_string(),
+        };
+
+        Some(HttpHeader::AccessControlAllowOrigin(
+            AccessControlAllowOrigin::new(allow_origin),
+        ))
+    }
+
+    fn vary_headers(&self, vary: &[&str]) -> Vec<HttpHeader> {
+        let varies_by_origin = self
+            .allowed_origins
+            .iter()
+            .any(|allowed_origin| !matches!(allowed_origin, AllowedOrigin::Any))
+            || self.allow_credentials;
+        if !varies_by_origin {
+            return vec![];
+        }
+
+        vec![HttpHeader::Vary(TokenList::new(
+            vary.iter().map(|name| name.to_string()).collect(),
+        ))]
+    }
+}
+
+fn glob_matches(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+    for (index, part) in parts.iter().enumerate() {
+        if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.is_empty()
+}
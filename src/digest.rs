@@ -0,0 +1,198 @@
+use std::{
+    collections::HashMap,
+    fs::Metadata,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use md5::Md5;
+use sha2::{Digest, Sha256, Sha512};
+use tokio::io::AsyncReadExt;
+
+use crate::{
+    http_header::{DigestFields, WantDigest},
+    http_request::HttpRequest,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Sha256 => "sha-256",
+            Self::Sha512 => "sha-512",
+        }
+    }
+
+    pub fn preferred(want_digest: &WantDigest) -> Option<Self> {
+        want_digest
+            .preferences()
+            .iter()
+            .filter(|(_, weight)| *weight > 0)
+            .filter_map(|(algorithm, weight)| {
+                let algorithm = Self::try_from(algorithm.as_str()).ok()?;
+
+                Some((algorithm, *weight))
+            })
+            .max_by_key(|(_, weight)| *weight)
+            .map(|(algorithm, _)| algorithm)
+    }
+}
+
+impl TryFrom<&str> for DigestAlgorithm {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "sha-256" => Ok(Self::Sha256),
+            "sha-512" => Ok(Self::Sha512),
+            _ => Err(()),
+        }
+    }
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Md5(Md5),
+}
+
+impl Hasher {
+    fn new(algorithm: DigestAlgorithm) -> Self {
+        match algorithm {
+            DigestAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            DigestAlgorithm::Sha512 => Self::Sha512(Sha512::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Sha512(hasher) => hasher.update(data),
+            Self::Md5(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        match self {
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+            Self::Sha512(hasher) => hasher.finalize().to_vec(),
+            Self::Md5(hasher) => hasher.finalize().to_vec(),
+        }
+    }
+}
+
+pub struct DigestVerifier {
+    expected_digests: Vec<(Vec<u8>, Hasher)>,
+}
+
+impl DigestVerifier {
+    pub fn from_request(http_request: &HttpRequest) -> Self {
+        let mut expected_digests = vec![];
+        if let Some(content_digest) = http_request.content_digest() {
+            for (algorithm, digest) in content_digest.digests() {
+                if let Ok(algorithm) = DigestAlgorithm::try_from(algorithm.as_str()) {
+                    expected_digests.push((digest.clone(), Hasher::new(algorithm)));
+                }
+            }
+        }
+        if let Some(content_md5) = http_request.content_md5() {
+            expected_digests.push((content_md5.digest().to_vec(), Hasher::Md5(Md5::new())));
+        }
+
+        Self { expected_digests }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for (_, hasher) in &mut self.expected_digests {
+            hasher.update(data);
+        }
+    }
+
+    pub fn verify(self) -> bool {
+        self.expected_digests
+            .into_iter()
+            .all(|(expected_digest, hasher)| hasher.finalize() == expected_digest)
+    }
+}
+
+struct CachedDigest {
+    modified: SystemTime,
+    len: u64,
+    digest: Vec<u8>,
+}
+
+pub struct DigestCache {
+    cached_digests: Mutex<HashMap<(PathBuf, DigestAlgorithm), CachedDigest>>,
+}
+
+impl DigestCache {
+    pub fn new() -> Self {
+        Self {
+            cached_digests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn digest_fields(
+        &self,
+        file_path: &Path,
+        metadata: &Metadata,
+        algorithm: DigestAlgorithm,
+    ) -> std::io::Result<DigestFields> {
+        let digest = self.digest(file_path, metadata, algorithm).await?;
+
+        Ok(DigestFields::new(vec![(
+            algorithm.name().to_string(),
+            digest,
+        )]))
+    }
+
+    pub async fn digest(
+        &self,
+        file_path: &Path,
+        metadata: &Metadata,
+        algorithm: DigestAlgorithm,
+    ) -> std::io::Result<Vec<u8>> {
+        let key = (file_path.to_path_buf(), algorithm);
+        let modified = metadata.modified()?;
+        {
+            let cached_digests = self.cached_digests.lock().expect("Digest cache is usable");
+            if let Some(cached_digest) = cached_digests.get(&key) {
+                if cached_digest.modified == modified && cached_digest.len == metadata.len() {
+                    return Ok(cached_digest.digest.clone());
+                }
+            }
+        }
+
+        let mut file = tokio::fs::File::open(file_path).await?;
+        let mut hasher = Hasher::new(algorithm);
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let read = file.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+        }
+        let digest = hasher.finalize();
+
+        self.cached_digests
+            .lock()
+            .expect("Digest cache is usable")
+            .insert(
+                key,
+                CachedDigest {
+                    modified,
+                    len: metadata.len(),
+                    digest: digest.clone(),
+                },
+            );
+
+        Ok(digest)
+    }
+}
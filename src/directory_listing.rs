@@ -0,0 +1,309 @@
+use std::{cmp::Ordering, path::Path, time::SystemTime};
+
+use serde_json::json;
+
+use crate::{
+    error_page::ErrorPageFormat,
+    http_header::{Accept, ContentType, HttpDate},
+    request_target::{percent_encode, QueryParameters},
+};
+
+const DEFAULT_PER_PAGE: usize = 100;
+const MAX_PER_PAGE: usize = 1000;
+
+#[derive(Clone, Copy)]
+pub struct DirectoryListing {
+    show_hidden: bool,
+}
+
+impl DirectoryListing {
+    pub fn new(show_hidden: bool) -> Self {
+        Self { show_hidden }
+    }
+
+    pub async fn render(
+        &self,
+        directory: &Path,
+        request_path: &str,
+        has_parent: bool,
+        query_parameters: &QueryParameters,
+        accept: Option<&Accept>,
+    ) -> std::io::Result<(ContentType, String)> {
+        let listing_query = ListingQuery::from(query_parameters);
+
+        let mut directory_entries = vec![];
+        let mut read_dir = tokio::fs::read_dir(directory).await?;
+        while let Some(dir_entry) = read_dir.next_entry().await? {
+            let name = dir_entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') && !self.show_hidden {
+                continue;
+            }
+            let metadata = dir_entry.metadata().await?;
+
+            directory_entries.push(DirectoryEntry {
+                name,
+                is_directory: metadata.is_dir(),
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+        directory_entries.sort_by(|a, b| listing_query.compare(a, b));
+
+        let total = directory_entries.len();
+        let page_entries = directory_entries
+            .iter()
+            .skip(
+                listing_query
+                    .page
+                    .saturating_sub(1)
+                    .saturating_mul(listing_query.per_page),
+            )
+            .take(listing_query.per_page)
+            .collect::<Vec<_>>();
+        let base_path = request_path.trim_end_matches('/');
+
+        let format = ErrorPageFormat::from_accept(accept);
+        let body = match format {
+            ErrorPageFormat::Html => {
+                render_html(base_path, has_parent, &page_entries, &listing_query, total)
+            }
+            ErrorPageFormat::Json => render_json(base_path, &page_entries, &listing_query, total),
+        };
+
+        Ok((format.content_type(), body))
+    }
+}
+
+struct DirectoryEntry {
+    name: String,
+    is_directory: bool,
+    size: u64,
+    modified: SystemTime,
+}
+
+impl DirectoryEntry {
+    fn entry_type(&self) -> &str {
+        if self.is_directory {
+            "directory"
+        } else {
+            "file"
+        }
+    }
+
+    fn href(&self, base_path: &str) -> String {
+        let mut href = format!("{}/{}", base_path, percent_encode(&self.name));
+        if self.is_directory {
+            href.push('/');
+        }
+
+        href
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortKey {
+    Name,
+    Size,
+    Modified,
+}
+
+impl SortKey {
+    fn value(&self) -> &str {
+        match self {
+            Self::Name => "name",
+            Self::Size => "size",
+            Self::Modified => "mtime",
+        }
+    }
+}
+
+impl TryFrom<&str> for SortKey {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "name" => Ok(Self::Name),
+            "size" => Ok(Self::Size),
+            "mtime" => Ok(Self::Modified),
+            _ => Err(()),
+        }
+    }
+}
+
+struct ListingQuery {
+    sort_key: SortKey,
+    descending: bool,
+    page: usize,
+    per_page: usize,
+}
+
+impl ListingQuery {
+    fn compare(&self, a: &DirectoryEntry, b: &DirectoryEntry) -> Ordering {
+        let ordering = match self.sort_key {
+            SortKey::Name => a.name.cmp(&b.name),
+            SortKey::Size => a.size.cmp(&b.size).then_with(|| a.name.cmp(&b.name)),
+            SortKey::Modified => a
+                .modified
+                .cmp(&b.modified)
+                .then_with(|| a.name.cmp(&b.name)),
+        };
+        let ordering = if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+
+        b.is_directory.cmp(&a.is_directory).then(ordering)
+    }
+
+    fn query_string(&self, sort_key: SortKey, descending: bool, page: usize) -> String {
+        format!(
+            "?sort={}&order={}&page={}&per_page={}",
+            sort_key.value(),
+            if descending { "desc" } else { "asc" },
+            page,
+            self.per_page
+        )
+    }
+}
+
+impl From<&QueryParameters> for ListingQuery {
+    fn from(value: &QueryParameters) -> Self {
+        let sort_key = value
+            .get("sort")
+            .and_then(|sort| SortKey::try_from(sort).ok())
+            .unwrap_or(SortKey::Name);
+        let page = value.parse::<usize>("page").ok().flatten().unwrap_or(1);
+        let per_page = value
+            .parse::<usize>("per_page")
+            .ok()
+            .flatten()
+            .unwrap_or(DEFAULT_PER_PAGE);
+
+        Self {
+            sort_key,
+            descending: value.get("order") == Some("desc"),
+            page: page.max(1),
+            per_page: per_page.clamp(1, MAX_PER_PAGE),
+        }
+    }
+}
+
+fn render_html(
+    base_path: &str,
+    has_parent: bool,
+    directory_entries: &[&DirectoryEntry],
+    listing_query: &ListingQuery,
+    total: usize,
+) -> String {
+    let title = escape_html(&format!("Index of {}/", base_path));
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><title>{title}</title></head>\n<body>\n<h1>{title}</h1>\n<table>\n<tr>"
+    );
+    for (sort_key, label) in [
+        (SortKey::Name, "Name"),
+        (SortKey::Size, "Size"),
+        (SortKey::Modified, "Modified"),
+    ] {
+        let descending = listing_query.sort_key == sort_key && !listing_query.descending;
+        html.push_str(&format!(
+            "<th><a href=\"{}\">{}</a></th>",
+            escape_html(&listing_query.query_string(sort_key, descending, 1)),
+            label
+        ));
+    }
+    html.push_str("<th>Type</th></tr>\n");
+    if has_parent {
+        let parent_path = &base_path[..base_path.rfind('/').unwrap_or_default()];
+
+        html.push_str(&format!(
+            "<tr><td><a href=\"{}/\">../</a></td><td></td><td></td><td>directory</td></tr>\n",
+            escape_html(parent_path)
+        ));
+    }
+    for directory_entry in directory_entries {
+        let name = if directory_entry.is_directory {
+            format!("{}/", directory_entry.name)
+        } else {
+            directory_entry.name.clone()
+        };
+        let size = if directory_entry.is_directory {
+            String::new()
+        } else {
+            directory_entry.size.to_string()
+        };
+
+        html.push_str(&format!(
+            "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&directory_entry.href(base_path)),
+            escape_html(&name),
+            size,
+            HttpDate::from(directory_entry.modified),
+            directory_entry.entry_type()
+        ));
+    }
+    html.push_str("</table>\n<p>");
+    if listing_query.page > 1 {
+        html.push_str(&format!(
+            "<a href=\"{}\">Previous</a> ",
+            escape_html(&listing_query.query_string(
+                listing_query.sort_key,
+                listing_query.descending,
+                listing_query.page - 1
+            ))
+        ));
+    }
+    if listing_query.page.saturating_mul(listing_query.per_page) < total {
+        html.push_str(&format!(
+            "<a href=\"{}\">Next</a>",
+            escape_html(&listing_query.query_string(
+                listing_query.sort_key,
+                listing_query.descending,
+                listing_query.page + 1
+            ))
+        ));
+    }
+    html.push_str("</p>\n</body>\n</html>\n");
+
+    html
+}
+
+fn render_json(
+    base_path: &str,
+    directory_entries: &[&DirectoryEntry],
+    listing_query: &ListingQuery,
+    total: usize,
+) -> String {
+    let entries = directory_entries
+        .iter()
+        .map(|directory_entry| {
+            json!({
+                "name": directory_entry.name,
+                "href": directory_entry.href(base_path),
+                "size": directory_entry.size,
+                "mtime": HttpDate::from(directory_entry.modified).to_string(),
+                "type": directory_entry.entry_type(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "path": format!("{}/", base_path),
+        "sort": listing_query.sort_key.value(),
+        "order": if listing_query.descending { "desc" } else { "asc" },
+        "page": listing_query.page,
+        "per_page": listing_query.per_page,
+        "total": total,
+        "entries": entries,
+    })
+    .to_string()
+}
+
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,82 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    http_header::{Accept, ContentType},
+    http_response::HttpResponseCode,
+};
+
+const DEFAULT_HTML_TEMPLATE: &str = "<!DOCTYPE html>
+<html>
+<head><title>{{status}} {{reason}}</title></head>
+<body><h1>{{status}} {{reason}}</h1></body>
+</html>
+";
+const DEFAULT_JSON_TEMPLATE: &str = "{\"status\": {{status}}, \"reason\": \"{{reason}}\"}";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorPageFormat {
+    Html,
+    Json,
+}
+
+impl ErrorPageFormat {
+    pub fn from_accept(accept: Option<&Accept>) -> Self {
+        let offers = [Self::Html.content_type(), Self::Json.content_type()];
+
+        match accept.and_then(|accept| accept.negotiate(&offers)) {
+            Some(content_type) if *content_type == Self::Json.content_type() => Self::Json,
+            _ => Self::Html,
+        }
+    }
+
+    pub fn content_type(&self) -> ContentType {
+        match self {
+            Self::Html => ContentType::text_html(),
+            Self::Json => ContentType::application_json(),
+        }
+    }
+}
+
+pub struct ErrorPages {
+    templates: HashMap<(u16, ErrorPageFormat), String>,
+}
+
+impl ErrorPages {
+    pub fn new() -> Self {
+        Self {
+            templates: HashMap::new(),
+        }
+    }
+
+    pub fn add_template(&mut self, code: u16, path: &Path) -> anyhow::Result<()> {
+        let format = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => ErrorPageFormat::Json,
+            Some("html") | Some("htm") => ErrorPageFormat::Html,
+            _ => anyhow::bail!("Error page {} should be .html or .json", path.display()),
+        };
+        let template = std::fs::read_to_string(path)?;
+        self.templates.insert((code, format), template);
+
+        Ok(())
+    }
+
+    pub fn render(
+        &self,
+        http_response_code: &HttpResponseCode,
+        accept: Option<&Accept>,
+    ) -> (ContentType, String) {
+        let format = ErrorPageFormat::from_accept(accept);
+        let template = match self.templates.get(&(http_response_code.code(), format)) {
+            Some(template) => template.as_str(),
+            None => match format {
+                ErrorPageFormat::Html => DEFAULT_HTML_TEMPLATE,
+                ErrorPageFormat::Json => DEFAULT_JSON_TEMPLATE,
+            },
+        };
+        let body = template
+            .replace("{{status}}", &http_response_code.code().to_string())
+            .replace("{{reason}}", http_response_code.reason_phrase());
+
+        (format.content_type(), body)
+    }
+}
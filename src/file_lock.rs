@@ -0,0 +1,30 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::OwnedMutexGuard;
+
+pub struct FileLocks {
+    locks: Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl FileLocks {
+    pub fn new() -> Self {
+        Self {
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn lock(&self, file_path: &Path) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().expect("File locks are usable");
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+
+            Arc::clone(locks.entry(file_path.to_path_buf()).or_default())
+        };
+
+        lock.lock_owned().await
+    }
+}
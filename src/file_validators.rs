@@ -0,0 +1,98 @@
+use std::{error::Error, fmt::Display, fs::Metadata, path::Path, time::UNIX_EPOCH};
+
+use crate::{
+    digest::{DigestAlgorithm, DigestCache},
+    http_header::{EntityTag, HttpDate},
+};
+
+#[derive(Clone, Copy)]
+pub enum EntityTagMode {
+    Metadata,
+    WeakMetadata,
+    ContentHash,
+}
+
+impl TryFrom<&str> for EntityTagMode {
+    type Error = EntityTagModeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "metadata" => Ok(Self::Metadata),
+            "weak-metadata" => Ok(Self::WeakMetadata),
+            "content-hash" => Ok(Self::ContentHash),
+            _ => Err(EntityTagModeError::new(value)),
+        }
+    }
+}
+
+pub struct FileValidators {
+    entity_tag: EntityTag,
+    last_modified: HttpDate,
+}
+
+impl FileValidators {
+    pub async fn for_file(
+        file_path: &Path,
+        metadata: &Metadata,
+        entity_tag_mode: EntityTagMode,
+        digest_cache: &DigestCache,
+    ) -> std::io::Result<Self> {
+        let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+        let metadata_tag = || {
+            let modified = modified
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+
+            format!("{:x}-{:x}", metadata.len(), modified)
+        };
+        let entity_tag = match entity_tag_mode {
+            EntityTagMode::Metadata => EntityTag::strong(metadata_tag()),
+            EntityTagMode::WeakMetadata => EntityTag::weak(metadata_tag()),
+            EntityTagMode::ContentHash => {
+                let hash = digest_cache
+                    .digest(file_path, metadata, DigestAlgorithm::Sha256)
+                    .await?
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<String>();
+
+                EntityTag::strong(hash)
+            }
+        };
+
+        Ok(Self {
+            entity_tag,
+            last_modified: HttpDate::from(modified),
+        })
+    }
+
+    pub fn entity_tag(&self) -> &EntityTag {
+        &self.entity_tag
+    }
+
+    pub fn last_modified(&self) -> &HttpDate {
+        &self.last_modified
+    }
+}
+
+#[derive(Debug)]
+pub struct EntityTagModeError {
+    entity_tag_mode: String,
+}
+
+impl EntityTagModeError {
+    fn new(entity_tag_mode: &str) -> Self {
+        Self {
+            entity_tag_mode: entity_tag_mode.to_string(),
+        }
+    }
+}
+
+impl Display for EntityTagModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid ETag mode: {}", self.entity_tag_mode)
+    }
+}
+
+impl Error for EntityTagModeError {}
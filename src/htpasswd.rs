@@ -0,0 +1,93 @@
+use std::{collections::HashMap, path::PathBuf, time::SystemTime};
+
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use tokio::sync::Mutex;
+
+pub struct HtpasswdFile {
+    path: PathBuf,
+    entries: Mutex<HtpasswdEntries>,
+}
+
+struct HtpasswdEntries {
+    modified: Option<SystemTime>,
+    hashes: HashMap<String, String>,
+}
+
+impl HtpasswdFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            entries: Mutex::new(HtpasswdEntries {
+                modified: None,
+                hashes: HashMap::new(),
+            }),
+        }
+    }
+
+    pub async fn verify(&self, user_id: &str, password: &str) -> bool {
+        let hash = {
+            let mut entries = self.entries.lock().await;
+            self.reload_if_changed(&mut entries).await;
+
+            entries.hashes.get(user_id).cloned()
+        };
+        let Some(hash) = hash else {
+            return false;
+        };
+        let password = password.to_string();
+
+        tokio::task::spawn_blocking(move || verify_hash(&password, &hash))
+            .await
+            .unwrap_or(false)
+    }
+
+    async fn reload_if_changed(&self, entries: &mut HtpasswdEntries) {
+        let modified = match tokio::fs::metadata(&self.path).await {
+            Ok(metadata) => metadata.modified().ok(),
+            Err(e) => {
+                println!("error: {e}");
+                return;
+            }
+        };
+        if modified.is_some() && modified == entries.modified {
+            return;
+        }
+
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => {
+                entries.hashes = parse_entries(&contents);
+                entries.modified = modified;
+            }
+            Err(e) => println!("error: {e}"),
+        }
+    }
+}
+
+fn parse_entries(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(user_id, hash)| (user_id.to_string(), hash.to_string()))
+        .collect()
+}
+
+fn verify_hash(password: &str, hash: &str) -> bool {
+    if hash.starts_with("$2a$") || hash.starts_with("$2b$") || hash.starts_with("$2y$") {
+        bcrypt::verify(password, hash).unwrap_or(false)
+    } else if hash.starts_with("$5$") {
+        sha_crypt::sha256_check(password, hash).is_ok()
+    } else if hash.starts_with("$6$") {
+        sha_crypt::sha512_check(password, hash).is_ok()
+    } else if hash.starts_with("$argon2") {
+        match PasswordHash::new(hash) {
+            Ok(password_hash) => Argon2::default()
+                .verify_password(password.as_bytes(), &password_hash)
+                .is_ok(),
+            Err(_) => false,
+        }
+    } else {
+        false
+    }
+}
@@ -1,4 +1,12 @@
-use std::{error::Error, fmt::Display};
+use std::{
+    error::Error,
+    fmt::Display,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+const MAX_BYTE_RANGES: usize = 16;
 
 pub enum HttpHeader {
     ContentType(ContentType),
@@ -6,6 +14,42 @@ pub enum HttpHeader {
     UserAgent(UserAgent),
     Host(Host),
     Accept(Accept),
+    Authorization(Authorization),
+    WwwAuthenticate(WwwAuthenticate),
+    Origin(Origin),
+    AccessControlRequestMethod(AccessControlRequestMethod),
+    AccessControlRequestHeaders(TokenList),
+    AccessControlAllowOrigin(AccessControlAllowOrigin),
+    AccessControlAllowMethods(TokenList),
+    AccessControlAllowHeaders(TokenList),
+    AccessControlAllowCredentials,
+    AccessControlMaxAge(AccessControlMaxAge),
+    AccessControlExposeHeaders(TokenList),
+    Vary(TokenList),
+    Allow(TokenList),
+    Range(Range),
+    IfRange(IfRange),
+    AcceptRanges(TokenList),
+    ContentRange(ContentRange),
+    XContentTypeOptions(TokenList),
+    Location(Location),
+    ETag(EntityTag),
+    LastModified(HttpDate),
+    IfNoneMatch(EntityTagMatch),
+    IfMatch(EntityTagMatch),
+    IfModifiedSince(HttpDate),
+    ContentDigest(DigestFields),
+    ReprDigest(DigestFields),
+    WantContentDigest(WantDigest),
+    WantReprDigest(WantDigest),
+    ContentMd5(ContentMd5),
+    ContentDisposition(ContentDisposition),
+    Cookie(Cookie),
+    SetCookie(SetCookie),
+    Connection(TokenList),
+    TransferEncoding(TokenList),
+    Expect(TokenList),
+    Other(OtherHeader),
 }
 
 impl Display for HttpHeader {
@@ -18,52 +62,259 @@ impl Display for HttpHeader {
             HttpHeader::UserAgent(user_agent) => write!(f, "User-Agent: {}", user_agent),
             HttpHeader::Host(host) => write!(f, "Host: {}", host),
             HttpHeader::Accept(accept) => write!(f, "Accept: {}", accept),
+            HttpHeader::Authorization(authorization) => {
+                write!(f, "Authorization: {}", authorization)
+            }
+            HttpHeader::WwwAuthenticate(www_authenticate) => {
+                write!(f, "WWW-Authenticate: {}", www_authenticate)
+            }
+            HttpHeader::Origin(origin) => write!(f, "Origin: {}", origin),
+            HttpHeader::AccessControlRequestMethod(request_method) => {
+                write!(f, "Access-Control-Request-Method: {}", request_method)
+            }
+            HttpHeader::AccessControlRequestHeaders(request_headers) => {
+                write!(f, "Access-Control-Request-Headers: {}", request_headers)
+            }
+            HttpHeader::AccessControlAllowOrigin(allow_origin) => {
+                write!(f, "Access-Control-Allow-Origin: {}", allow_origin)
+            }
+            HttpHeader::AccessControlAllowMethods(allow_methods) => {
+                write!(f, "Access-Control-Allow-Methods: {}", allow_methods)
+            }
+            HttpHeader::AccessControlAllowHeaders(allow_headers) => {
+                write!(f, "Access-Control-Allow-Headers: {}", allow_headers)
+            }
+            HttpHeader::AccessControlAllowCredentials => {
+                write!(f, "Access-Control-Allow-Credentials: true")
+            }
+            HttpHeader::AccessControlMaxAge(max_age) => {
+                write!(f, "Access-Control-Max-Age: {}", max_age)
+            }
+            HttpHeader::AccessControlExposeHeaders(expose_headers) => {
+                write!(f, "Access-Control-Expose-Headers: {}", expose_headers)
+            }
+            HttpHeader::Vary(vary) => write!(f, "Vary: {}", vary),
+            HttpHeader::Allow(allow) => write!(f, "Allow: {}", allow),
+            HttpHeader::Range(range) => write!(f, "Range: {}", range),
+            HttpHeader::IfRange(if_range) => write!(f, "If-Range: {}", if_range),
+            HttpHeader::AcceptRanges(accept_ranges) => {
+                write!(f, "Accept-Ranges: {}", accept_ranges)
+            }
+            HttpHeader::ContentRange(content_range) => {
+                write!(f, "Content-Range: {}", content_range)
+            }
+            HttpHeader::XContentTypeOptions(x_content_type_options) => {
+                write!(f, "X-Content-Type-Options: {}", x_content_type_options)
+            }
+            HttpHeader::Location(location) => write!(f, "Location: {}", location),
+            HttpHeader::ETag(entity_tag) => write!(f, "ETag: {}", entity_tag),
+            HttpHeader::LastModified(last_modified) => {
+                write!(f, "Last-Modified: {}", last_modified)
+            }
+            HttpHeader::IfNoneMatch(if_none_match) => write!(f, "If-None-Match: {}", if_none_match),
+            HttpHeader::IfMatch(if_match) => write!(f, "If-Match: {}", if_match),
+            HttpHeader::IfModifiedSince(if_modified_since) => {
+                write!(f, "If-Modified-Since: {}", if_modified_since)
+            }
+            HttpHeader::ContentDigest(content_digest) => {
+                write!(f, "Content-Digest: {}", content_digest)
+            }
+            HttpHeader::ReprDigest(repr_digest) => write!(f, "Repr-Digest: {}", repr_digest),
+            HttpHeader::WantContentDigest(want_content_digest) => {
+                write!(f, "Want-Content-Digest: {}", want_content_digest)
+            }
+            HttpHeader::WantReprDigest(want_repr_digest) => {
+                write!(f, "Want-Repr-Digest: {}", want_repr_digest)
+            }
+            HttpHeader::ContentMd5(content_md5) => write!(f, "Content-MD5: {}", content_md5),
+            HttpHeader::ContentDisposition(content_disposition) => {
+                write!(f, "Content-Disposition: {}", content_disposition)
+            }
+            HttpHeader::Cookie(cookie) => write!(f, "Cookie: {}", cookie),
+            HttpHeader::SetCookie(set_cookie) => write!(f, "Set-Cookie: {}", set_cookie),
+            HttpHeader::Connection(connection) => write!(f, "Connection: {}", connection),
+            HttpHeader::TransferEncoding(transfer_encoding) => {
+                write!(f, "Transfer-Encoding: {}", transfer_encoding)
+            }
+            HttpHeader::Expect(expect) => write!(f, "Expect: {}", expect),
+            HttpHeader::Other(other_header) => write!(f, "{}", other_header),
         }
     }
 }
 
-impl TryFrom<&str> for HttpHeader {
-    type Error = HttpHeaderError;
+impl HttpHeader {
+    pub fn try_new(name: &str, value: &str) -> Result<Self, HttpHeaderError> {
+        match (name.to_ascii_lowercase().as_str(), value) {
+            ("host", host) => Ok(HttpHeader::Host(Host::new(host.to_string()))),
+            ("user-agent", user_agent) => Ok(HttpHeader::UserAgent(UserAgent::new(
+                user_agent.to_string(),
+            ))),
+            ("accept", accept) => Ok(HttpHeader::Accept(Accept::from(accept))),
+            ("content-type", content_type) => {
+                let content_type = ContentType::try_from(content_type)?;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let http_header_parts = value.split_once(": ");
-
-        if let Some(http_header_parts) = http_header_parts {
-            match http_header_parts {
-                ("Host", host) => Ok(HttpHeader::Host(Host::new(host.to_string()))),
-                ("User-Agent", user_agent) => Ok(HttpHeader::UserAgent(UserAgent::new(
-                    user_agent.to_string(),
-                ))),
-                ("Accept", accept) => Ok(HttpHeader::Accept(Accept::new(accept.to_string()))),
-                ("Content-Type", content_type) => {
-                    let content_type = ContentType::try_from(content_type)?;
-
-                    Ok(HttpHeader::ContentType(content_type))
-                }
-                ("Content-Length", content_length) => {
-                    let content_length = ContentLength::try_from(content_length)?;
+                Ok(HttpHeader::ContentType(content_type))
+            }
+            ("content-length", content_length) => {
+                let content_length = ContentLength::try_from(content_length)?;
+
+                Ok(HttpHeader::ContentLength(content_length))
+            }
+            ("authorization", authorization) => {
+                let authorization = Authorization::try_from(authorization)?;
 
-                    Ok(HttpHeader::ContentLength(content_length))
+                Ok(HttpHeader::Authorization(authorization))
+            }
+            ("origin", origin) => Ok(HttpHeader::Origin(Origin::new(origin.to_string()))),
+            ("access-control-request-method", request_method) => {
+                Ok(HttpHeader::AccessControlRequestMethod(
+                    AccessControlRequestMethod::new(request_method.to_string()),
+                ))
+            }
+            ("access-control-request-headers", request_headers) => Ok(
+                HttpHeader::AccessControlRequestHeaders(TokenList::from(request_headers)),
+            ),
+            ("range", range) => Ok(HttpHeader::Range(Range::from(range))),
+            ("if-range", if_range) => Ok(HttpHeader::IfRange(IfRange::from(if_range))),
+            ("if-none-match", if_none_match) => {
+                Ok(HttpHeader::IfNoneMatch(EntityTagMatch::from(if_none_match)))
+            }
+            ("if-match", if_match) => Ok(HttpHeader::IfMatch(EntityTagMatch::from(if_match))),
+            ("content-digest", content_digest) => Ok(HttpHeader::ContentDigest(
+                DigestFields::from(content_digest),
+            )),
+            ("repr-digest", repr_digest) => {
+                Ok(HttpHeader::ReprDigest(DigestFields::from(repr_digest)))
+            }
+            ("want-content-digest", want_content_digest) => Ok(HttpHeader::WantContentDigest(
+                WantDigest::from(want_content_digest),
+            )),
+            ("want-repr-digest", want_repr_digest) => Ok(HttpHeader::WantReprDigest(
+                WantDigest::from(want_repr_digest),
+            )),
+            ("content-disposition", content_disposition) => Ok(HttpHeader::ContentDisposition(
+                ContentDisposition::from(content_disposition),
+            )),
+            ("cookie", cookie) => Ok(HttpHeader::Cookie(Cookie::from(cookie))),
+            ("connection", connection) => Ok(HttpHeader::Connection(TokenList::from(connection))),
+            ("transfer-encoding", transfer_encoding) => Ok(HttpHeader::TransferEncoding(
+                TokenList::from(transfer_encoding),
+            )),
+            ("expect", expect) => Ok(HttpHeader::Expect(TokenList::from(expect))),
+            ("content-md5", content_md5) => {
+                Ok(HttpHeader::ContentMd5(ContentMd5::from(content_md5)))
+            }
+            ("if-modified-since", if_modified_since) => {
+                match HttpDate::try_from(if_modified_since) {
+                    Ok(if_modified_since) => Ok(HttpHeader::IfModifiedSince(if_modified_since)),
+                    Err(_) => Ok(HttpHeader::Other(OtherHeader::new(
+                        String::from("If-Modified-Since"),
+                        if_modified_since.to_string(),
+                    ))),
                 }
-                _ => Err(HttpHeaderError::ParseString(value.to_string())),
             }
-        } else {
-            Err(HttpHeaderError::ParseString(value.to_string()))
+            (_, other_value) => Ok(HttpHeader::Other(OtherHeader::new(
+                name.to_string(),
+                other_value.to_string(),
+            ))),
         }
     }
 }
 
-pub enum ContentType {
-    TextPlain,
-    ApplicationOctetStream,
+impl TryFrom<&str> for HttpHeader {
+    type Error = HttpHeaderError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (name, header_value) = value
+            .split_once(':')
+            .ok_or_else(|| HttpHeaderError::ParseString(value.to_string()))?;
+
+        HttpHeader::try_new(name, header_value.trim_matches([' ', '\t']))
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ContentType {
+    media_type: String,
+    subtype: String,
+    parameters: Vec<(String, String)>,
+}
+
+impl ContentType {
+    pub fn new(media_type: &str, subtype: &str) -> Self {
+        Self {
+            media_type: media_type.to_ascii_lowercase(),
+            subtype: subtype.to_ascii_lowercase(),
+            parameters: vec![],
+        }
+    }
+
+    pub fn text_plain() -> Self {
+        Self::new("text", "plain")
+    }
+
+    pub fn text_html() -> Self {
+        Self::new("text", "html")
+    }
+
+    pub fn application_json() -> Self {
+        Self::new("application", "json")
+    }
+
+    pub fn application_octet_stream() -> Self {
+        Self::new("application", "octet-stream")
+    }
+
+    pub fn multipart_byteranges(boundary: &str) -> Self {
+        Self::new("multipart", "byteranges").with_parameter("boundary", boundary)
+    }
+
+    pub fn with_parameter(mut self, name: &str, value: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        self.parameters
+            .retain(|(parameter_name, _)| *parameter_name != name);
+        self.parameters.push((name, value.to_string()));
+
+        self
+    }
+
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+
+    pub fn subtype(&self) -> &str {
+        &self.subtype
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(parameter_name, _)| parameter_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn charset(&self) -> Option<&str> {
+        self.parameter("charset")
+    }
+
+    pub fn boundary(&self) -> Option<&str> {
+        self.parameter("boundary")
+    }
 }
 
 impl Display for ContentType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ContentType::TextPlain => write!(f, "text/plain"),
-            ContentType::ApplicationOctetStream => write!(f, "application/octet-stream"),
+        write!(f, "{}/{}", self.media_type, self.subtype)?;
+        for (name, value) in &self.parameters {
+            if !value.is_empty() && value.chars().all(is_token_char) {
+                write!(f, "; {}={}", name, value)?;
+            } else {
+                let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+                write!(f, "; {}=\"{}\"", name, value)?;
+            }
         }
+
+        Ok(())
     }
 }
 
@@ -71,12 +322,120 @@ impl TryFrom<&str> for ContentType {
     type Error = ContentTypeError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "text/plain" => Ok(Self::TextPlain),
-            "application/octet-stream" => Ok(Self::ApplicationOctetStream),
-            _ => Err(ContentTypeError::new(value)),
+        let mut parts = value.splitn(2, ';');
+        let essence = parts.next().unwrap_or_default().trim();
+        let (media_type, subtype) = essence
+            .split_once('/')
+            .filter(|(media_type, subtype)| {
+                !media_type.is_empty()
+                    && !subtype.is_empty()
+                    && media_type.chars().all(is_token_char)
+                    && subtype.chars().all(is_token_char)
+            })
+            .ok_or_else(|| ContentTypeError::new(value))?;
+
+        let mut content_type = Self::new(media_type, subtype);
+        for (name, parameter_value) in parse_parameters(parts.next().unwrap_or_default()) {
+            content_type = content_type.with_parameter(&name, &parameter_value);
+        }
+
+        Ok(content_type)
+    }
+}
+
+pub struct ContentDisposition {
+    disposition_type: String,
+    parameters: Vec<(String, String)>,
+}
+
+impl ContentDisposition {
+    pub fn disposition_type(&self) -> &str {
+        &self.disposition_type
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(parameter_name, _)| parameter_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.parameter("name")
+    }
+
+    pub fn filename(&self) -> Option<&str> {
+        self.parameter("filename")
+    }
+}
+
+impl Display for ContentDisposition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.disposition_type)?;
+        for (name, value) in &self.parameters {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            write!(f, "; {}=\"{}\"", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&str> for ContentDisposition {
+    fn from(value: &str) -> Self {
+        let mut parts = value.splitn(2, ';');
+        let disposition_type = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+
+        Self {
+            disposition_type,
+            parameters: parse_parameters(parts.next().unwrap_or_default()),
+        }
+    }
+}
+
+fn parse_parameters(mut parameters: &str) -> Vec<(String, String)> {
+    let mut parsed_parameters = vec![];
+    while let Some((name, rest)) = parameters.split_once('=') {
+        let name = name.trim_start_matches(|c: char| c == ';' || c.is_ascii_whitespace());
+        let (parameter_value, rest) = match rest.strip_prefix('"') {
+            Some(quoted) => {
+                let mut parameter_value = String::new();
+                let mut chars = quoted.char_indices();
+                let mut end = quoted.len();
+                while let Some((index, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                parameter_value.push(escaped);
+                            }
+                        }
+                        '"' => {
+                            end = index + 1;
+                            break;
+                        }
+                        _ => parameter_value.push(c),
+                    }
+                }
+
+                (parameter_value, &quoted[end..])
+            }
+            None => {
+                let end = rest.find(';').unwrap_or(rest.len());
+
+                (rest[..end].trim().to_string(), &rest[end..])
+            }
+        };
+        if !name.trim().is_empty() {
+            parsed_parameters.push((name.trim().to_ascii_lowercase(), parameter_value));
         }
+        parameters = rest;
     }
+
+    parsed_parameters
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
 }
 
 #[derive(Debug)]
@@ -84,6 +443,7 @@ pub enum HttpHeaderError {
     ParseString(String),
     InvalidContentType(ContentTypeError),
     InvalidContentLength(ContentLengthError),
+    InvalidAuthorization(AuthorizationError),
 }
 
 impl Display for HttpHeaderError {
@@ -92,6 +452,7 @@ impl Display for HttpHeaderError {
             Self::ParseString(s) => write!(f, "Failed to parse header from string: {}", s),
             Self::InvalidContentType(content_type_err) => write!(f, "{}", content_type_err),
             Self::InvalidContentLength(content_length_err) => write!(f, "{}", content_length_err),
+            Self::InvalidAuthorization(authorization_err) => write!(f, "{}", authorization_err),
         }
     }
 }
@@ -110,6 +471,12 @@ impl From<ContentLengthError> for HttpHeaderError {
     }
 }
 
+impl From<AuthorizationError> for HttpHeaderError {
+    fn from(value: AuthorizationError) -> Self {
+        Self::InvalidAuthorization(value)
+    }
+}
+
 pub struct ContentLength(usize);
 
 impl ContentLength {
@@ -132,6 +499,9 @@ impl TryFrom<&str> for ContentLength {
     type Error = ContentLengthError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ContentLengthError::new(value));
+        }
         let content_length = value.parse();
 
         match content_length {
@@ -165,6 +535,47 @@ impl Host {
     fn new(host: String) -> Self {
         Self(host)
     }
+
+    pub fn hostname(&self) -> String {
+        let host = self.0.trim();
+        let hostname = match host.rfind(']') {
+            Some(end) => &host[..=end],
+            None => host.split_once(':').map_or(host, |(hostname, _)| hostname),
+        };
+
+        hostname.to_ascii_lowercase()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let host = self.0.trim();
+        let (hostname, port) = match host.rfind(']') {
+            Some(end) => host.split_at(end + 1),
+            None => host.split_at(host.find(':').unwrap_or(host.len())),
+        };
+        let hostname_valid = match hostname
+            .strip_prefix('[')
+            .and_then(|hostname| hostname.strip_suffix(']'))
+        {
+            Some(ip_literal) => {
+                !ip_literal.is_empty()
+                    && ip_literal
+                        .chars()
+                        .all(|c| c.is_ascii_hexdigit() || c == ':' || c == '.')
+            }
+            None => {
+                !hostname.is_empty()
+                    && hostname
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "-._~!$&'()*+,;=%".contains(c))
+            }
+        };
+        let port_valid = port.is_empty()
+            || port
+                .strip_prefix(':')
+                .is_some_and(|port| port.chars().all(|c| c.is_ascii_digit()));
+
+        hostname_valid && port_valid
+    }
 }
 
 impl Display for Host {
@@ -173,58 +584,1296 @@ impl Display for Host {
     }
 }
 
-pub struct Accept(String);
+pub struct Accept(Vec<MediaRange>);
 
 impl Accept {
-    pub fn new(accept: String) -> Self {
-        Self(accept)
+    pub fn quality(&self, content_type: &ContentType) -> u16 {
+        self.0
+            .iter()
+            .filter(|media_range| media_range.matches(content_type))
+            .max_by_key(|media_range| media_range.specificity())
+            .map(|media_range| media_range.quality)
+            .unwrap_or(0)
+    }
+
+    pub fn negotiate<'a>(&self, offers: &'a [ContentType]) -> Option<&'a ContentType> {
+        if self.0.is_empty() {
+            return offers.first();
+        }
+
+        offers
+            .iter()
+            .enumerate()
+            .map(|(index, offer)| (index, offer, self.quality(offer)))
+            .filter(|(_, _, quality)| *quality > 0)
+            .max_by_key(|(index, _, quality)| (*quality, std::cmp::Reverse(*index)))
+            .map(|(_, offer, _)| offer)
     }
 }
 
 impl Display for Accept {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        let accept = self
+            .0
+            .iter()
+            .map(MediaRange::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(f, "{}", accept)
     }
 }
 
-#[derive(Debug)]
-pub struct ContentTypeError {
-    content_type: String,
+impl From<&str> for Accept {
+    fn from(value: &str) -> Self {
+        Self(
+            value
+                .split(',')
+                .filter_map(|media_range| MediaRange::try_from(media_range.trim()).ok())
+                .collect(),
+        )
+    }
 }
 
-impl ContentTypeError {
-    fn new(content_type: &str) -> Self {
-        Self {
-            content_type: content_type.to_string(),
+pub struct MediaRange {
+    media_type: String,
+    subtype: String,
+    parameters: Vec<(String, String)>,
+    quality: u16,
+}
+
+impl MediaRange {
+    fn matches(&self, content_type: &ContentType) -> bool {
+        (self.media_type == "*" || self.media_type == content_type.media_type())
+            && (self.subtype == "*" || self.subtype == content_type.subtype())
+            && self.parameters.iter().all(|(name, value)| {
+                content_type
+                    .parameter(name)
+                    .is_some_and(|parameter_value| parameter_value.eq_ignore_ascii_case(value))
+            })
+    }
+
+    fn specificity(&self) -> usize {
+        usize::from(self.media_type != "*")
+            + usize::from(self.subtype != "*")
+            + self.parameters.len()
+    }
+}
+
+impl Display for MediaRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.media_type, self.subtype)?;
+        for (name, value) in &self.parameters {
+            write!(f, ";{}={}", name, value)?;
         }
+        if self.quality < 1000 {
+            let quality = format!("{:.3}", f64::from(self.quality) / 1000.0);
+            write!(
+                f,
+                ";q={}",
+                quality.trim_end_matches('0').trim_end_matches('.')
+            )?;
+        }
+
+        Ok(())
     }
 }
 
-impl Display for ContentTypeError {
+impl TryFrom<&str> for MediaRange {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (essence, parameters) = value.split_once(';').unwrap_or((value, ""));
+        let (media_type, subtype) = match essence.trim() {
+            "*" => ("*", "*"),
+            essence => essence.split_once('/').ok_or(())?,
+        };
+        if media_type.is_empty()
+            || subtype.is_empty()
+            || !media_type.chars().all(is_token_char)
+            || !subtype.chars().all(is_token_char)
+            || (media_type == "*" && subtype != "*")
+        {
+            return Err(());
+        }
+
+        let mut media_range_parameters = vec![];
+        let mut quality = 1000;
+        for (name, parameter_value) in parse_parameters(parameters) {
+            if name == "q" {
+                quality = parse_quality(&parameter_value).ok_or(())?;
+                break;
+            }
+            media_range_parameters.push((name, parameter_value));
+        }
+
+        Ok(Self {
+            media_type: media_type.to_ascii_lowercase(),
+            subtype: subtype.to_ascii_lowercase(),
+            parameters: media_range_parameters,
+            quality,
+        })
+    }
+}
+
+fn parse_quality(value: &str) -> Option<u16> {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let fraction = format!("{:0<3}", fraction).parse::<u16>().ok()?;
+    match integer {
+        "0" => Some(fraction),
+        "1" if fraction == 0 => Some(1000),
+        _ => None,
+    }
+}
+
+pub enum Authorization {
+    Basic(BasicCredentials),
+    Bearer(String),
+    Other(String),
+}
+
+impl Display for Authorization {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Invalid content type: {}", self.content_type)
+        match self {
+            Self::Basic(basic_credentials) => write!(f, "Basic {}", basic_credentials),
+            Self::Bearer(token) => write!(f, "Bearer {}", token),
+            Self::Other(authorization) => write!(f, "{}", authorization),
+        }
     }
 }
 
-impl Error for ContentTypeError {}
+impl TryFrom<&str> for Authorization {
+    type Error = AuthorizationError;
 
-#[derive(Debug)]
-pub struct ContentLengthError {
-    content_length: String,
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.split_once(' ') {
+            Some((scheme, credentials)) if scheme.eq_ignore_ascii_case("Basic") => {
+                let basic_credentials = BasicCredentials::try_from(credentials.trim())
+                    .map_err(|_| AuthorizationError::new(value))?;
+
+                Ok(Self::Basic(basic_credentials))
+            }
+            Some((scheme, token)) if scheme.eq_ignore_ascii_case("Bearer") => {
+                Ok(Self::Bearer(token.trim().to_string()))
+            }
+            Some(_) => Ok(Self::Other(value.to_string())),
+            None => Err(AuthorizationError::new(value)),
+        }
+    }
 }
 
-impl ContentLengthError {
-    fn new(content_type: &str) -> Self {
+pub struct BasicCredentials {
+    user_id: String,
+    password: String,
+}
+
+impl BasicCredentials {
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl Display for BasicCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let credentials = format!("{}:{}", self.user_id, self.password);
+
+        write!(f, "{}", STANDARD.encode(credentials))
+    }
+}
+
+impl TryFrom<&str> for BasicCredentials {
+    type Error = AuthorizationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let credentials = STANDARD
+            .decode(value)
+            .map_err(|_| AuthorizationError::new(value))?;
+        let credentials =
+            String::from_utf8(credentials).map_err(|_| AuthorizationError::new(value))?;
+        let (user_id, password) = credentials
+            .split_once(':')
+            .ok_or_else(|| AuthorizationError::new(value))?;
+
+        Ok(Self {
+            user_id: user_id.to_string(),
+            password: password.to_string(),
+        })
+    }
+}
+
+pub struct WwwAuthenticate {
+    scheme: String,
+    realm: String,
+}
+
+impl WwwAuthenticate {
+    pub fn new(scheme: &str, realm: &str) -> Self {
         Self {
-            content_length: content_type.to_string(),
+            scheme: scheme.to_string(),
+            realm: realm.to_string(),
         }
     }
 }
 
-impl Display for ContentLengthError {
+impl Display for WwwAuthenticate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Invalid content type: {}", self.content_length)
+        write!(f, "{} realm=\"{}\"", self.scheme, self.realm)
     }
 }
 
-impl Error for ContentLengthError {}
+pub struct Origin(String);
+
+impl Origin {
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+
+    fn new(origin: String) -> Self {
+        Self(origin)
+    }
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub struct AccessControlRequestMethod(String);
+
+impl AccessControlRequestMethod {
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+
+    fn new(request_method: String) -> Self {
+        Self(request_method)
+    }
+}
+
+impl Display for AccessControlRequestMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub struct AccessControlAllowOrigin(String);
+
+impl AccessControlAllowOrigin {
+    pub fn new(allow_origin: String) -> Self {
+        Self(allow_origin)
+    }
+}
+
+impl Display for AccessControlAllowOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub struct Location(String);
+
+impl Location {
+    pub fn new(location: String) -> Self {
+        Self(location)
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub struct AccessControlMaxAge(u64);
+
+impl AccessControlMaxAge {
+    pub fn new(max_age: u64) -> Self {
+        Self(max_age)
+    }
+}
+
+impl Display for AccessControlMaxAge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenList(Vec<String>);
+
+impl TokenList {
+    pub fn new(tokens: Vec<String>) -> Self {
+        Self(tokens)
+    }
+
+    pub fn tokens(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl Display for TokenList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(", "))
+    }
+}
+
+impl From<&str> for TokenList {
+    fn from(value: &str) -> Self {
+        Self(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+}
+
+pub enum Range {
+    Bytes(Vec<ByteRangeSpec>),
+    Other(String),
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bytes(byte_range_specs) => {
+                let byte_range_specs = byte_range_specs
+                    .iter()
+                    .map(ByteRangeSpec::to_string)
+                    .collect::<Vec<_>>();
+
+                write!(f, "bytes={}", byte_range_specs.join(","))
+            }
+            Self::Other(range) => write!(f, "{}", range),
+        }
+    }
+}
+
+impl From<&str> for Range {
+    fn from(value: &str) -> Self {
+        let byte_range_specs = match value.split_once('=') {
+            Some((unit, byte_range_specs)) if unit.trim().eq_ignore_ascii_case("bytes") => {
+                byte_range_specs
+                    .split(',')
+                    .map(|byte_range_spec| ByteRangeSpec::try_from(byte_range_spec.trim()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()
+            }
+            _ => None,
+        };
+
+        match byte_range_specs {
+            Some(byte_range_specs)
+                if !byte_range_specs.is_empty() && byte_range_specs.len() <= MAX_BYTE_RANGES =>
+            {
+                Self::Bytes(byte_range_specs)
+            }
+            _ => Self::Other(value.to_string()),
+        }
+    }
+}
+
+pub enum ByteRangeSpec {
+    FromTo(u64, u64),
+    From(u64),
+    Suffix(u64),
+}
+
+impl ByteRangeSpec {
+    pub fn resolve(&self, complete_length: u64) -> Option<(u64, u64)> {
+        match *self {
+            Self::FromTo(first, last) if first < complete_length => {
+                Some((first, last.min(complete_length - 1)))
+            }
+            Self::From(first) if first < complete_length => Some((first, complete_length - 1)),
+            Self::Suffix(suffix_length) if suffix_length > 0 && complete_length > 0 => Some((
+                complete_length.saturating_sub(suffix_length),
+                complete_length - 1,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ByteRangeSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FromTo(first, last) => write!(f, "{}-{}", first, last),
+            Self::From(first) => write!(f, "{}-", first),
+            Self::Suffix(suffix_length) => write!(f, "-{}", suffix_length),
+        }
+    }
+}
+
+impl TryFrom<&str> for ByteRangeSpec {
+    type Error = RangeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let parse = |position: &str| position.parse().map_err(|_| RangeError::new(value));
+
+        match value.split_once('-') {
+            Some(("", suffix_length)) => Ok(Self::Suffix(parse(suffix_length)?)),
+            Some((first, "")) => Ok(Self::From(parse(first)?)),
+            Some((first, last)) => {
+                let (first, last) = (parse(first)?, parse(last)?);
+                if first > last {
+                    return Err(RangeError::new(value));
+                }
+
+                Ok(Self::FromTo(first, last))
+            }
+            None => Err(RangeError::new(value)),
+        }
+    }
+}
+
+pub enum IfRange {
+    EntityTag(EntityTag),
+    Date(HttpDate),
+    Other(String),
+}
+
+impl IfRange {
+    pub fn matches(&self, entity_tag: &EntityTag, last_modified: &HttpDate) -> bool {
+        match self {
+            Self::EntityTag(if_range) => if_range.strong_eq(entity_tag),
+            Self::Date(if_range) => if_range == last_modified,
+            Self::Other(_) => false,
+        }
+    }
+}
+
+impl Display for IfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EntityTag(entity_tag) => write!(f, "{}", entity_tag),
+            Self::Date(http_date) => write!(f, "{}", http_date),
+            Self::Other(if_range) => write!(f, "{}", if_range),
+        }
+    }
+}
+
+impl From<&str> for IfRange {
+    fn from(value: &str) -> Self {
+        if let Ok(entity_tag) = EntityTag::try_from(value) {
+            Self::EntityTag(entity_tag)
+        } else if let Ok(http_date) = HttpDate::try_from(value) {
+            Self::Date(http_date)
+        } else {
+            Self::Other(value.to_string())
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct EntityTag {
+    weak: bool,
+    opaque_tag: String,
+}
+
+impl EntityTag {
+    pub fn strong(opaque_tag: String) -> Self {
+        Self {
+            weak: false,
+            opaque_tag,
+        }
+    }
+
+    pub fn weak(opaque_tag: String) -> Self {
+        Self {
+            weak: true,
+            opaque_tag,
+        }
+    }
+
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.opaque_tag == other.opaque_tag
+    }
+
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.opaque_tag == other.opaque_tag
+    }
+}
+
+impl Display for EntityTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.weak {
+            write!(f, "W/")?;
+        }
+
+        write!(f, "\"{}\"", self.opaque_tag)
+    }
+}
+
+impl TryFrom<&str> for EntityTag {
+    type Error = EntityTagError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (weak, opaque_tag) = match value.strip_prefix("W/") {
+            Some(opaque_tag) => (true, opaque_tag),
+            None => (false, value),
+        };
+        let opaque_tag = opaque_tag
+            .strip_prefix('"')
+            .and_then(|opaque_tag| opaque_tag.strip_suffix('"'))
+            .filter(|opaque_tag| !opaque_tag.contains('"'))
+            .ok_or_else(|| EntityTagError::new(value))?;
+
+        Ok(Self {
+            weak,
+            opaque_tag: opaque_tag.to_string(),
+        })
+    }
+}
+
+pub enum EntityTagMatch {
+    Any,
+    EntityTags(Vec<EntityTag>),
+}
+
+impl EntityTagMatch {
+    pub fn strong_matches(&self, entity_tag: &EntityTag) -> bool {
+        match self {
+            Self::Any => true,
+            Self::EntityTags(entity_tags) => entity_tags
+                .iter()
+                .any(|candidate| candidate.strong_eq(entity_tag)),
+        }
+    }
+
+    pub fn weak_matches(&self, entity_tag: &EntityTag) -> bool {
+        match self {
+            Self::Any => true,
+            Self::EntityTags(entity_tags) => entity_tags
+                .iter()
+                .any(|candidate| candidate.weak_eq(entity_tag)),
+        }
+    }
+}
+
+impl Display for EntityTagMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Any => write!(f, "*"),
+            Self::EntityTags(entity_tags) => {
+                let entity_tags = entity_tags
+                    .iter()
+                    .map(EntityTag::to_string)
+                    .collect::<Vec<_>>();
+
+                write!(f, "{}", entity_tags.join(", "))
+            }
+        }
+    }
+}
+
+impl From<&str> for EntityTagMatch {
+    fn from(value: &str) -> Self {
+        if value.trim() == "*" {
+            return Self::Any;
+        }
+
+        Self::EntityTags(
+            value
+                .split(',')
+                .filter_map(|entity_tag| EntityTag::try_from(entity_tag.trim()).ok())
+                .collect(),
+        )
+    }
+}
+
+const DAY_NAMES: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+pub struct HttpDate(u64);
+
+impl HttpDate {
+    fn from_parts(
+        year: i64,
+        month: u32,
+        day: u32,
+        hour: u64,
+        minute: u64,
+        second: u64,
+    ) -> Option<Self> {
+        if !(0..=9999).contains(&year)
+            || !(1..=12).contains(&month)
+            || !(1..=days_in_month(year, month)).contains(&day)
+            || hour > 23
+            || minute > 59
+            || second > 60
+        {
+            return None;
+        }
+
+        let days = days_from_civil(year, month, day);
+        let days = u64::try_from(days).ok()?;
+
+        Some(Self(days * 86400 + hour * 3600 + minute * 60 + second))
+    }
+}
+
+impl From<SystemTime> for HttpDate {
+    fn from(value: SystemTime) -> Self {
+        let seconds = value
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+
+        Self(seconds)
+    }
+}
+
+impl Display for HttpDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let days = self.0 / 86400;
+        let seconds_of_day = self.0 % 86400;
+        let (year, month, day) = civil_from_days(days as i64);
+
+        write!(
+            f,
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            DAY_NAMES[(days % 7) as usize],
+            day,
+            MONTH_NAMES[month as usize - 1],
+            year,
+            seconds_of_day / 3600,
+            seconds_of_day % 3600 / 60,
+            seconds_of_day % 60
+        )
+    }
+}
+
+impl TryFrom<&str> for HttpDate {
+    type Error = HttpDateError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let parts = value.split_ascii_whitespace().collect::<Vec<_>>();
+        let month = |name: &str| {
+            MONTH_NAMES
+                .iter()
+                .position(|month_name| *month_name == name)
+                .map(|month| month as u32 + 1)
+        };
+        let time = |time: &str| -> Option<(u64, u64, u64)> {
+            let mut time_parts = time.split(':').map(|time_part| time_part.parse().ok());
+            let time = (
+                time_parts.next()??,
+                time_parts.next()??,
+                time_parts.next()??,
+            );
+
+            time_parts.next().is_none().then_some(time)
+        };
+
+        let http_date = match parts[..] {
+            // IMF-fixdate: Sun, 06 Nov 1994 08:49:37 GMT
+            [_, day, month_name, year, clock, "GMT"] => {
+                let (hour, minute, second) =
+                    time(clock).ok_or_else(|| HttpDateError::new(value))?;
+
+                Self::from_parts(
+                    year.parse().map_err(|_| HttpDateError::new(value))?,
+                    month(month_name).ok_or_else(|| HttpDateError::new(value))?,
+                    day.parse().map_err(|_| HttpDateError::new(value))?,
+                    hour,
+                    minute,
+                    second,
+                )
+            }
+            // RFC 850: Sunday, 06-Nov-94 08:49:37 GMT
+            [_, date, clock, "GMT"] => {
+                let mut date_parts = date.split('-');
+                let (Some(day), Some(month_name), Some(year), None) = (
+                    date_parts.next(),
+                    date_parts.next(),
+                    date_parts.next(),
+                    date_parts.next(),
+                ) else {
+                    return Err(HttpDateError::new(value));
+                };
+                let year: i64 = year.parse().map_err(|_| HttpDateError::new(value))?;
+                let year = if year < 70 { 2000 + year } else { 1900 + year };
+                let (hour, minute, second) =
+                    time(clock).ok_or_else(|| HttpDateError::new(value))?;
+
+                Self::from_parts(
+                    year,
+                    month(month_name).ok_or_else(|| HttpDateError::new(value))?,
+                    day.parse().map_err(|_| HttpDateError::new(value))?,
+                    hour,
+                    minute,
+                    second,
+                )
+            }
+            // asctime: Sun Nov  6 08:49:37 1994
+            [_, month_name, day, clock, year] => {
+                let (hour, minute, second) =
+                    time(clock).ok_or_else(|| HttpDateError::new(value))?;
+
+                Self::from_parts(
+                    year.parse().map_err(|_| HttpDateError::new(value))?,
+                    month(month_name).ok_or_else(|| HttpDateError::new(value))?,
+                    day.parse().map_err(|_| HttpDateError::new(value))?,
+                    hour,
+                    minute,
+                    second,
+                )
+            }
+            _ => None,
+        };
+
+        http_date.ok_or_else(|| HttpDateError::new(value))
+    }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Howard Hinnant's days_from_civil and civil_from_days.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+pub enum ContentRange {
+    Satisfied {
+        first: u64,
+        last: u64,
+        complete_length: u64,
+    },
+    Unsatisfied {
+        complete_length: u64,
+    },
+}
+
+impl Display for ContentRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Satisfied {
+                first,
+                last,
+                complete_length,
+            } => write!(f, "bytes {}-{}/{}", first, last, complete_length),
+            Self::Unsatisfied { complete_length } => write!(f, "bytes */{}", complete_length),
+        }
+    }
+}
+
+pub struct DigestFields(Vec<(String, Vec<u8>)>);
+
+impl DigestFields {
+    pub fn new(digests: Vec<(String, Vec<u8>)>) -> Self {
+        Self(digests)
+    }
+
+    pub fn digests(&self) -> &[(String, Vec<u8>)] {
+        &self.0
+    }
+}
+
+impl Display for DigestFields {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digests = self
+            .0
+            .iter()
+            .map(|(algorithm, digest)| format!("{}=:{}:", algorithm, STANDARD.encode(digest)))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", digests.join(", "))
+    }
+}
+
+impl From<&str> for DigestFields {
+    fn from(value: &str) -> Self {
+        Self(
+            value
+                .split(',')
+                .filter_map(|digest| digest.trim().split_once('='))
+                .map(|(algorithm, digest)| {
+                    let digest = digest
+                        .strip_prefix(':')
+                        .and_then(|digest| digest.strip_suffix(':'))
+                        .and_then(|digest| STANDARD.decode(digest).ok())
+                        .unwrap_or_default();
+
+                    (algorithm.to_ascii_lowercase(), digest)
+                })
+                .collect(),
+        )
+    }
+}
+
+pub struct WantDigest(Vec<(String, u8)>);
+
+impl WantDigest {
+    pub fn preferences(&self) -> &[(String, u8)] {
+        &self.0
+    }
+}
+
+impl Display for WantDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let preferences = self
+            .0
+            .iter()
+            .map(|(algorithm, weight)| format!("{}={}", algorithm, weight))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", preferences.join(", "))
+    }
+}
+
+impl From<&str> for WantDigest {
+    fn from(value: &str) -> Self {
+        Self(
+            value
+                .split(',')
+                .filter_map(|preference| preference.trim().split_once('='))
+                .filter_map(|(algorithm, weight)| {
+                    let weight = weight.trim().parse().ok()?;
+
+                    Some((algorithm.trim().to_ascii_lowercase(), weight))
+                })
+                .collect(),
+        )
+    }
+}
+
+pub struct ContentMd5(Vec<u8>);
+
+impl ContentMd5 {
+    pub fn digest(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for ContentMd5 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", STANDARD.encode(&self.0))
+    }
+}
+
+impl From<&str> for ContentMd5 {
+    fn from(value: &str) -> Self {
+        Self(STANDARD.decode(value.trim()).unwrap_or_default())
+    }
+}
+
+pub struct Cookie(Vec<(String, String)>);
+
+impl Cookie {
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+impl Display for Cookie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cookie = self
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        write!(f, "{}", cookie)
+    }
+}
+
+impl From<&str> for Cookie {
+    fn from(value: &str) -> Self {
+        Self(
+            value
+                .split(';')
+                .filter_map(|cookie_pair| {
+                    let (name, value) = cookie_pair.trim().split_once('=')?;
+                    let name = name.trim();
+                    if name.is_empty() || !name.chars().all(is_token_char) {
+                        return None;
+                    }
+                    let value = value.trim();
+                    let value = value
+                        .strip_prefix('"')
+                        .and_then(|value| value.strip_suffix('"'))
+                        .unwrap_or(value);
+
+                    Some((name.to_string(), value.to_string()))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl Display for SameSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Strict => write!(f, "Strict"),
+            Self::Lax => write!(f, "Lax"),
+            Self::None => write!(f, "None"),
+        }
+    }
+}
+
+impl TryFrom<&str> for SameSite {
+    type Error = CookieError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_ascii_lowercase().as_str() {
+            "strict" => Ok(Self::Strict),
+            "lax" => Ok(Self::Lax),
+            "none" => Ok(Self::None),
+            _ => Err(CookieError::new(value)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SetCookie {
+    name: String,
+    value: String,
+    expires: Option<HttpDate>,
+    max_age: Option<i64>,
+    domain: Option<String>,
+    path: Option<String>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+    partitioned: bool,
+}
+
+impl SetCookie {
+    pub fn try_new(name: &str, value: &str) -> Result<Self, CookieError> {
+        if name.is_empty() || !name.chars().all(is_token_char) {
+            return Err(CookieError::new(name));
+        }
+        if !value.chars().all(is_cookie_octet) {
+            return Err(CookieError::new(value));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+            partitioned: false,
+        })
+    }
+
+    pub fn with_expires(mut self, expires: HttpDate) -> Self {
+        self.expires = Some(expires);
+
+        self
+    }
+
+    pub fn with_max_age(mut self, max_age: i64) -> Self {
+        self.max_age = Some(max_age);
+
+        self
+    }
+
+    pub fn with_domain(mut self, domain: &str) -> Result<Self, CookieError> {
+        if domain.is_empty() || !domain.chars().all(is_cookie_attribute_char) {
+            return Err(CookieError::new(domain));
+        }
+        self.domain = Some(domain.to_string());
+
+        Ok(self)
+    }
+
+    pub fn with_path(mut self, path: &str) -> Result<Self, CookieError> {
+        if !path.starts_with('/') || !path.chars().all(is_cookie_attribute_char) {
+            return Err(CookieError::new(path));
+        }
+        self.path = Some(path.to_string());
+
+        Ok(self)
+    }
+
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+
+        self
+    }
+
+    pub fn with_http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+
+        self
+    }
+
+    pub fn with_same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+
+        self
+    }
+
+    pub fn with_partitioned(mut self, partitioned: bool) -> Self {
+        self.partitioned = partitioned;
+
+        self
+    }
+}
+
+impl Display for SetCookie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(expires) = &self.expires {
+            write!(f, "; Expires={}", expires)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age)?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        // Browsers reject SameSite=None and Partitioned cookies that are not Secure.
+        if self.secure || self.partitioned || self.same_site == Some(SameSite::None) {
+            write!(f, "; Secure")?;
+        }
+        if self.http_only {
+            write!(f, "; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+        if self.partitioned {
+            write!(f, "; Partitioned")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn is_cookie_octet(c: char) -> bool {
+    c.is_ascii_graphic() && !matches!(c, '"' | ',' | ';' | '\\')
+}
+
+fn is_cookie_attribute_char(c: char) -> bool {
+    (c.is_ascii_graphic() || c == ' ') && c != ';'
+}
+
+pub struct OtherHeader {
+    name: String,
+    value: String,
+}
+
+impl OtherHeader {
+    fn new(name: String, value: String) -> Self {
+        Self { name, value }
+    }
+}
+
+impl Display for OtherHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
+#[derive(Debug)]
+pub struct ContentTypeError {
+    content_type: String,
+}
+
+impl ContentTypeError {
+    fn new(content_type: &str) -> Self {
+        Self {
+            content_type: content_type.to_string(),
+        }
+    }
+}
+
+impl Display for ContentTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid content type: {}", self.content_type)
+    }
+}
+
+impl Error for ContentTypeError {}
+
+#[derive(Debug)]
+pub struct ContentLengthError {
+    content_length: String,
+}
+
+impl ContentLengthError {
+    fn new(content_length: &str) -> Self {
+        Self {
+            content_length: content_length.to_string(),
+        }
+    }
+}
+
+impl Display for ContentLengthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid content length: {}", self.content_length)
+    }
+}
+
+impl Error for ContentLengthError {}
+
+#[derive(Debug)]
+pub struct AuthorizationError {
+    authorization: String,
+}
+
+impl AuthorizationError {
+    fn new(authorization: &str) -> Self {
+        Self {
+            authorization: authorization.to_string(),
+        }
+    }
+}
+
+impl Display for AuthorizationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid authorization: {}", self.authorization)
+    }
+}
+
+impl Error for AuthorizationError {}
+
+#[derive(Debug)]
+pub struct RangeError {
+    range: String,
+}
+
+impl RangeError {
+    fn new(range: &str) -> Self {
+        Self {
+            range: range.to_string(),
+        }
+    }
+}
+
+impl Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid range: {}", self.range)
+    }
+}
+
+impl Error for RangeError {}
+
+#[derive(Debug)]
+pub struct EntityTagError {
+    entity_tag: String,
+}
+
+impl EntityTagError {
+    fn new(entity_tag: &str) -> Self {
+        Self {
+            entity_tag: entity_tag.to_string(),
+        }
+    }
+}
+
+impl Display for EntityTagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid entity tag: {}", self.entity_tag)
+    }
+}
+
+impl Error for EntityTagError {}
+
+#[derive(Debug)]
+pub struct HttpDateError {
+    http_date: String,
+}
+
+impl HttpDateError {
+    fn new(http_date: &str) -> Self {
+        Self {
+            http_date: http_date.to_string(),
+        }
+    }
+}
+
+impl Display for HttpDateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid HTTP date: {}", self.http_date)
+    }
+}
+
+impl Error for HttpDateError {}
+
+#[derive(Debug)]
+pub struct CookieError {
+    cookie: String,
+}
+
+impl CookieError {
+    fn new(cookie: &str) -> Self {
+        Self {
+            cookie: cookie.to_string(),
+        }
+    }
+}
+
+impl Display for CookieError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid cookie: {}", self.cookie)
+    }
+}
+
+impl Error for CookieError {}
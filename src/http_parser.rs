@@ -0,0 +1,436 @@
+use std::{borrow::Cow, error::Error, fmt::Display};
+
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, take_while, take_while1, take_while_m_n},
+    character::streaming::{char, crlf, hex_digit1},
+    combinator::{map, map_res, opt, recognize},
+    error::{context, VerboseError, VerboseErrorKind},
+    multi::{many0, many0_count},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    IResult,
+};
+
+type ParseResult<'a, T> = IResult<&'a [u8], T, VerboseError<&'a [u8]>>;
+
+pub type Parsed<T> = Result<Option<(T, usize)>, ParseError>;
+
+#[derive(Debug)]
+pub struct RequestHead<'a> {
+    pub http_method: &'a str,
+    pub request_target: &'a str,
+    pub http_version: Option<&'a str>,
+    pub fields: Vec<(&'a str, Cow<'a, str>)>,
+}
+
+pub struct RequestHeadParser {
+    checked: usize,
+    scanned: usize,
+    request_line_checked: bool,
+}
+
+impl RequestHeadParser {
+    pub fn new() -> Self {
+        Self {
+            checked: 0,
+            scanned: 0,
+            request_line_checked: false,
+        }
+    }
+
+    pub fn parse<'a>(&mut self, input: &'a [u8]) -> Parsed<RequestHead<'a>> {
+        while let Some(line_end) = input[self.scanned..].iter().position(|&b| b == b'\n') {
+            self.scanned += line_end + 1;
+            let rest = &input[self.checked..];
+            let checked = if !self.request_line_checked {
+                parse(rest, |input| {
+                    map(preceded(many0_count(crlf), request_line), |_| ())(input)
+                })
+            } else if rest.starts_with(b"\r\n") {
+                return parse(input, request_head);
+            } else {
+                parse(rest, |input| {
+                    map(context("header section", field_line), |_| ())(input)
+                })
+            };
+            match checked.map_err(|e| e.offset(self.checked))? {
+                Some((_, consumed)) => {
+                    self.checked += consumed;
+                    self.request_line_checked = true;
+                }
+                None => return Ok(None),
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+pub fn parse_chunk_size(input: &[u8]) -> Parsed<u64> {
+    parse(input, chunk_size_line)
+}
+
+pub fn parse_chunk_data_end(input: &[u8]) -> Parsed<()> {
+    parse(input, |input| {
+        map(context("chunk data", crlf), |_| ())(input)
+    })
+}
+
+pub fn parse_trailer_section(input: &[u8]) -> Parsed<()> {
+    parse(input, |input| {
+        map(context("trailer section", field_section), |_| ())(input)
+    })
+}
+
+fn parse<'a, T>(
+    input: &'a [u8],
+    mut parser: impl FnMut(&'a [u8]) -> ParseResult<'a, T>,
+) -> Parsed<T> {
+    match parser(input) {
+        Ok((rest, parsed)) => Ok(Some((parsed, input.len() - rest.len()))),
+        Err(nom::Err::Incomplete(_)) => Ok(None),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::new(input, e)),
+    }
+}
+
+fn request_head(input: &[u8]) -> ParseResult<'_, RequestHead<'_>> {
+    let (input, _) = many0_count(crlf)(input)?;
+    let (input, (http_method, request_target, http_version)) = request_line(input)?;
+    let (input, fields) = context("header section", field_section)(input)?;
+
+    Ok((
+        input,
+        RequestHead {
+            http_method,
+            request_target,
+            http_version,
+            fields,
+        },
+    ))
+}
+
+fn request_line(input: &[u8]) -> ParseResult<'_, (&str, &str, Option<&str>)> {
+    context(
+        "request line",
+        tuple((
+            token,
+            preceded(char(' '), request_target),
+            alt((
+                map(delimited(char(' '), http_version, crlf), Some),
+                map(crlf, |_| None),
+            )),
+        )),
+    )(input)
+}
+
+fn request_target(input: &[u8]) -> ParseResult<'_, &str> {
+    map_res(take_while1(is_vchar), std::str::from_utf8)(input)
+}
+
+fn http_version(input: &[u8]) -> ParseResult<'_, &str> {
+    map_res(
+        recognize(tuple((
+            tag("HTTP/"),
+            take_while_m_n(1, 1, |b: u8| b.is_ascii_digit()),
+            char('.'),
+            take_while_m_n(1, 1, |b: u8| b.is_ascii_digit()),
+        ))),
+        std::str::from_utf8,
+    )(input)
+}
+
+fn field_section(input: &[u8]) -> ParseResult<'_, Vec<(&str, Cow<'_, str>)>> {
+    terminated(many0(field_line), crlf)(input)
+}
+
+fn field_line(input: &[u8]) -> ParseResult<'_, (&str, Cow<'_, str>)> {
+    let (input, name) = terminated(token, char(':'))(input)?;
+    let (input, value) = delimited(
+        ows,
+        take_while(|b| is_field_vchar(b) || is_whitespace(b)),
+        crlf,
+    )(input)?;
+    let value_end = value
+        .iter()
+        .rposition(|&b| !is_whitespace(b))
+        .map_or(0, |position| position + 1);
+
+    Ok((input, (name, String::from_utf8_lossy(&value[..value_end]))))
+}
+
+fn chunk_size_line(input: &[u8]) -> ParseResult<'_, u64> {
+    context(
+        "chunk size",
+        terminated(
+            map_res(map_res(hex_digit1, std::str::from_utf8), |chunk_size| {
+                u64::from_str_radix(chunk_size, 16)
+            }),
+            pair(many0_count(chunk_extension), crlf),
+        ),
+    )(input)
+}
+
+fn chunk_extension(input: &[u8]) -> ParseResult<'_, ()> {
+    map(
+        tuple((
+            ows,
+            char(';'),
+            ows,
+            token,
+            opt(tuple((ows, char('='), ows, alt((token, quoted_string))))),
+        )),
+        |_| (),
+    )(input)
+}
+
+fn quoted_string(input: &[u8]) -> ParseResult<'_, &str> {
+    map_res(
+        recognize(delimited(
+            char('"'),
+            many0_count(alt((
+                take_while1(is_qdtext),
+                recognize(pair(
+                    char('\\'),
+                    take_while_m_n(1, 1, |b| is_field_vchar(b) || is_whitespace(b)),
+                )),
+            ))),
+            char('"'),
+        )),
+        std::str::from_utf8,
+    )(input)
+}
+
+fn token(input: &[u8]) -> ParseResult<'_, &str> {
+    map_res(take_while1(is_tchar), std::str::from_utf8)(input)
+}
+
+fn ows(input: &[u8]) -> ParseResult<'_, &[u8]> {
+    take_while(is_whitespace)(input)
+}
+
+fn is_tchar(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+fn is_vchar(b: u8) -> bool {
+    (0x21..=0x7e).contains(&b)
+}
+
+fn is_field_vchar(b: u8) -> bool {
+    is_vchar(b) || b >= 0x80
+}
+
+fn is_qdtext(b: u8) -> bool {
+    is_whitespace(b) || (is_field_vchar(b) && b != b'"' && b != b'\\')
+}
+
+fn is_whitespace(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    context: &'static str,
+    position: usize,
+}
+
+impl ParseError {
+    fn new(input: &[u8], verbose_error: VerboseError<&[u8]>) -> Self {
+        let context = verbose_error
+            .errors
+            .iter()
+            .rev()
+            .find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(context) => Some(*context),
+                _ => None,
+            })
+            .unwrap_or("message");
+        let position = verbose_error
+            .errors
+            .first()
+            .map_or(0, |(rest, _)| input.len() - rest.len());
+
+        Self { context, position }
+    }
+
+    fn offset(mut self, offset: usize) -> Self {
+        self.position += offset;
+
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid {} at byte {}", self.context, self.position)
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse_head(input: &[u8]) -> Parsed<RequestHead<'_>> {
+        RequestHeadParser::new().parse(input)
+    }
+
+    #[test]
+    fn request_head_parses_complete_input() {
+        let input = b"GET /echo/a HTTP/1.1\r\nHost: x\r\nAccept: */*\r\n\r\nbody";
+        let (request_head, consumed) = parse_head(input).unwrap().unwrap();
+
+        assert_eq!(request_head.http_method, "GET");
+        assert_eq!(request_head.request_target, "/echo/a");
+        assert_eq!(request_head.http_version, Some("HTTP/1.1"));
+        assert_eq!(
+            request_head.fields,
+            vec![("Host", Cow::from("x")), ("Accept", Cow::from("*/*"))]
+        );
+        assert_eq!(consumed, input.len() - 4);
+    }
+
+    #[test]
+    fn request_head_is_incomplete_on_partial_input() {
+        for input in [
+            &b""[..],
+            b"GE",
+            b"GET / HTTP/1.1",
+            b"GET / HTTP/1.1\r",
+            b"GET / HTTP/1.1\r\nHo",
+            b"GET / HTTP/1.1\r\nHost: x\r\n",
+            b"GET / HTTP/1.1\r\nHost: x\r\n\r",
+        ] {
+            assert!(parse_head(input).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn request_head_is_the_same_when_fed_incrementally() {
+        let input = b"\r\nPOST /echo HTTP/1.1\r\nHost: x\r\nContent-Length: 2\r\n\r\n{}";
+        let mut request_head_parser = RequestHeadParser::new();
+        let mut parsed = None;
+        for end in 0..=input.len() {
+            if let Some((request_head, consumed)) =
+                request_head_parser.parse(&input[..end]).unwrap()
+            {
+                parsed = Some((request_head.fields.len(), consumed));
+                break;
+            }
+        }
+
+        assert_eq!(parsed, Some((2, input.len() - 2)));
+    }
+
+    #[test]
+    fn request_head_errors_before_the_end_of_the_head() {
+        let e = parse_head(b"GET  / HTTP/1.1\r\n").unwrap_err();
+
+        assert_eq!(e.context, "request line");
+        assert_eq!(e.position, 4);
+    }
+
+    #[test]
+    fn request_head_reports_byte_positions_in_later_lines() {
+        let e = parse_head(b"GET / HTTP/1.1\r\nHost: x\r\nBad\x01: y\r\n").unwrap_err();
+
+        assert_eq!(e.context, "header section");
+        assert_eq!(e.position, 28);
+        assert_eq!(e.to_string(), "Invalid header section at byte 28");
+    }
+
+    #[test]
+    fn request_head_rejects_whitespace_before_the_colon() {
+        let e = parse_head(b"GET / HTTP/1.1\r\nHost : x\r\n\r\n").unwrap_err();
+
+        assert_eq!(e.context, "header section");
+        assert_eq!(e.position, 20);
+    }
+
+    #[test]
+    fn request_head_rejects_obsolete_line_folding() {
+        assert!(parse_head(b"GET / HTTP/1.1\r\nHost: x\r\n folded\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn request_head_rejects_bare_line_feeds() {
+        assert!(parse_head(b"GET / HTTP/1.1\nHost: x\n\n").is_err());
+    }
+
+    #[test]
+    fn request_head_trims_optional_whitespace_around_field_values() {
+        let input = b"GET / HTTP/1.1\r\nHost:\t x \t\r\nX-Empty:\r\nX-Inner: a \t b\r\n\r\n";
+        let (request_head, _) = parse_head(input).unwrap().unwrap();
+
+        assert_eq!(
+            request_head.fields,
+            vec![
+                ("Host", Cow::from("x")),
+                ("X-Empty", Cow::from("")),
+                ("X-Inner", Cow::from("a \t b")),
+            ]
+        );
+    }
+
+    #[test]
+    fn request_head_without_version_is_http_0_9() {
+        let (request_head, _) = parse_head(b"GET /\r\n\r\n").unwrap().unwrap();
+
+        assert_eq!(request_head.http_version, None);
+    }
+
+    #[test]
+    fn chunk_size_parses_extensions_with_quoted_strings() {
+        let input = b"1a ; name=\"va\\\"l ue\";flag;token=x\r\ndata";
+
+        assert_eq!(
+            parse_chunk_size(input).unwrap(),
+            Some((26, input.len() - 4))
+        );
+    }
+
+    #[test]
+    fn chunk_size_is_incomplete_on_partial_input() {
+        for input in [&b"1a"[..], b"1a;name=\"val", b"1a;name=x\r"] {
+            assert!(parse_chunk_size(input).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn chunk_size_rejects_unterminated_extensions() {
+        let e = parse_chunk_size(b"1a;=x\r\n").unwrap_err();
+
+        assert_eq!(e.context, "chunk size");
+    }
+
+    #[test]
+    fn chunk_size_rejects_oversized_hex() {
+        let e = parse_chunk_size(b"10000000000000000\r\n").unwrap_err();
+
+        assert_eq!(e.context, "chunk size");
+        assert_eq!(e.position, 0);
+    }
+
+    #[test]
+    fn chunk_data_end_requires_crlf() {
+        assert_eq!(parse_chunk_data_end(b"\r\n").unwrap(), Some(((), 2)));
+        assert!(parse_chunk_data_end(b"\r").unwrap().is_none());
+        assert!(parse_chunk_data_end(b"xx").is_err());
+    }
+
+    #[test]
+    fn trailer_section_parses_fields_and_final_crlf() {
+        assert_eq!(parse_trailer_section(b"\r\n").unwrap(), Some(((), 2)));
+        assert_eq!(
+            parse_trailer_section(b"X-Trailer: 1\r\n\r\nnext").unwrap(),
+            Some(((), 16))
+        );
+        assert!(parse_trailer_section(b"X-Trailer: 1\r\n")
+            .unwrap()
+            .is_none());
+        assert!(parse_trailer_section(b"X-Trailer 1\r\n\r\n").is_err());
+    }
+}
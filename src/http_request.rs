@@ -1,12 +1,73 @@
-use std::slice::Iter;
+use std::{collections::HashMap, error::Error, fmt::Display, slice::Iter};
+
+use bytes::BytesMut;
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+
+use crate::{
+    auth::Principal,
+    http_header::{
+        Accept, AccessControlRequestMethod, Authorization, ContentMd5, ContentType, DigestFields,
+        EntityTagMatch, Host, HttpDate, HttpHeader, IfRange, Origin, Range, TokenList, UserAgent,
+        WantDigest,
+    },
+    http_parser::{ParseError, RequestHeadParser},
+    http_response::HttpResponseCode,
+    http_stream::HttpStream,
+    jwt::Claims,
+    request_target::{QueryParameters, RequestTarget},
+    tls::ClientCertificate,
+};
+
+const MAX_REQUEST_HEAD_SIZE: usize = 64 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HttpVersion {
+    Http10,
+    Http11,
+}
+
+impl Display for HttpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http10 => write!(f, "HTTP/1.0"),
+            Self::Http11 => write!(f, "HTTP/1.1"),
+        }
+    }
+}
 
-use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+impl TryFrom<&str> for HttpVersion {
+    type Error = HttpRequestError;
 
-use crate::http_header::{HttpHeader, UserAgent};
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (major, minor) = value
+            .strip_prefix("HTTP/")
+            .and_then(|version| version.split_once('.'))
+            .filter(|(major, minor)| {
+                major.len() == 1
+                    && minor.len() == 1
+                    && major
+                        .chars()
+                        .chain(minor.chars())
+                        .all(|c| c.is_ascii_digit())
+            })
+            .ok_or_else(|| HttpRequestError::Malformed(format!("bad HTTP version {}", value)))?;
+
+        match (major, minor) {
+            ("1", "0") => Ok(Self::Http10),
+            ("1", _) => Ok(Self::Http11),
+            _ => Err(HttpRequestError::UnsupportedVersion(value.to_string())),
+        }
+    }
+}
 
+#[derive(PartialEq)]
 pub enum HttpMethod {
     Get,
     Post,
+    Put,
+    Options,
 }
 
 impl TryFrom<&str> for HttpMethod {
@@ -16,6 +77,8 @@ impl TryFrom<&str> for HttpMethod {
         match value {
             "GET" => Ok(Self::Get),
             "POST" => Ok(Self::Post),
+            "PUT" => Ok(Self::Put),
+            "OPTIONS" => Ok(Self::Options),
             _ => Err(HttpMethodError),
         }
     }
@@ -24,12 +87,29 @@ impl TryFrom<&str> for HttpMethod {
 pub struct HttpRequest {
     request_line: RequestLine,
     http_headers: HttpHeaders,
-    body: Option<String>,
+    client_certificate: Option<ClientCertificate>,
+    principal: Option<Principal>,
 }
 
 impl HttpRequest {
-    pub fn request_target(&self) -> &str {
-        &self.request_line.request_target
+    pub fn path(&self) -> &str {
+        self.request_line.request_target.path()
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.request_line.request_target.query()
+    }
+
+    pub fn path_segments(&self) -> &[String] {
+        self.request_line.request_target.path_segments()
+    }
+
+    pub fn decoded_path(&self) -> String {
+        self.path_segments().join("/")
+    }
+
+    pub fn query_parameters(&self) -> &QueryParameters {
+        self.request_line.request_target.query_parameters()
     }
 
     pub fn user_agent(&self) -> Option<&UserAgent> {
@@ -42,92 +122,595 @@ impl HttpRequest {
         None
     }
 
-    pub async fn from_tcp_stream(stream: &mut tokio::net::TcpStream) -> Self {
-        let mut buf_reader = tokio::io::BufReader::new(stream);
+    pub fn authorization(&self) -> Option<&Authorization> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::Authorization(authorization) = http_header {
+                return Some(authorization);
+            }
+        }
 
-        let mut request_line = String::new();
-        buf_reader
-            .read_line(&mut request_line)
-            .await
-            .expect("Can read request line from tcp stream");
-        let request_line = request_line.trim_end();
-        let request_line = RequestLine::from(request_line);
-
-        let mut http_headers = HttpHeaders::new();
-        let mut header = String::new();
-        buf_reader
-            .read_line(&mut header)
-            .await
-            .expect("Can read headers from tcp stream");
-        while !header.trim_end().is_empty() {
-            http_headers.add(
-                HttpHeader::try_from(header.trim_end())
-                    .expect("All headers received should be valid"),
-            );
-            header.clear();
-            buf_reader
-                .read_line(&mut header)
-                .await
-                .expect("Can read headers from tcp stream");
-        }
-
-        let content_length = http_headers.content_length();
-
-        let body = if let Some(content_length) = content_length {
-            let mut buf = vec![0; content_length];
-            buf_reader
-                .read_exact(&mut buf)
-                .await
-                .expect("Can read request body");
-
-            Some(String::from_utf8(buf).expect("Request body is valid utf8 string"))
-        } else {
-            None
+        None
+    }
+
+    pub fn accept(&self) -> Option<&Accept> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::Accept(accept) = http_header {
+                return Some(accept);
+            }
+        }
+
+        None
+    }
+
+    pub fn negotiate<'a>(&self, offers: &'a [ContentType]) -> Option<&'a ContentType> {
+        match self.accept() {
+            Some(accept) => accept.negotiate(offers),
+            None => offers.first(),
+        }
+    }
+
+    pub fn range(&self) -> Option<&Range> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::Range(range) = http_header {
+                return Some(range);
+            }
+        }
+
+        None
+    }
+
+    pub fn if_range(&self) -> Option<&IfRange> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::IfRange(if_range) = http_header {
+                return Some(if_range);
+            }
+        }
+
+        None
+    }
+
+    pub fn if_none_match(&self) -> Option<&EntityTagMatch> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::IfNoneMatch(if_none_match) = http_header {
+                return Some(if_none_match);
+            }
+        }
+
+        None
+    }
+
+    pub fn if_match(&self) -> Option<&EntityTagMatch> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::IfMatch(if_match) = http_header {
+                return Some(if_match);
+            }
+        }
+
+        None
+    }
+
+    pub fn if_modified_since(&self) -> Option<&HttpDate> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::IfModifiedSince(if_modified_since) = http_header {
+                return Some(if_modified_since);
+            }
+        }
+
+        None
+    }
+
+    pub fn cookies(&self) -> HashMap<&str, &str> {
+        let mut cookies = HashMap::new();
+        for http_header in &self.http_headers {
+            if let HttpHeader::Cookie(cookie) = http_header {
+                for (name, value) in cookie.iter() {
+                    cookies.entry(name).or_insert(value);
+                }
+            }
+        }
+
+        cookies
+    }
+
+    pub fn content_type(&self) -> Option<&ContentType> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::ContentType(content_type) = http_header {
+                return Some(content_type);
+            }
+        }
+
+        None
+    }
+
+    pub fn content_digest(&self) -> Option<&DigestFields> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::ContentDigest(content_digest) = http_header {
+                return Some(content_digest);
+            }
+        }
+
+        None
+    }
+
+    pub fn content_md5(&self) -> Option<&ContentMd5> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::ContentMd5(content_md5) = http_header {
+                return Some(content_md5);
+            }
+        }
+
+        None
+    }
+
+    pub fn want_content_digest(&self) -> Option<&WantDigest> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::WantContentDigest(want_content_digest) = http_header {
+                return Some(want_content_digest);
+            }
+        }
+
+        None
+    }
+
+    pub fn want_repr_digest(&self) -> Option<&WantDigest> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::WantReprDigest(want_repr_digest) = http_header {
+                return Some(want_repr_digest);
+            }
+        }
+
+        None
+    }
+
+    pub fn origin(&self) -> Option<&Origin> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::Origin(origin) = http_header {
+                return Some(origin);
+            }
+        }
+
+        None
+    }
+
+    pub fn access_control_request_method(&self) -> Option<&AccessControlRequestMethod> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::AccessControlRequestMethod(request_method) = http_header {
+                return Some(request_method);
+            }
+        }
+
+        None
+    }
+
+    pub fn access_control_request_headers(&self) -> Option<&TokenList> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::AccessControlRequestHeaders(request_headers) = http_header {
+                return Some(request_headers);
+            }
+        }
+
+        None
+    }
+
+    pub fn is_cors_preflight(&self) -> bool {
+        self.request_line.http_method == HttpMethod::Options
+            && self.origin().is_some()
+            && self.access_control_request_method().is_some()
+    }
+
+    pub async fn from_tcp_stream(
+        buf_reader: &mut BufReader<HttpStream>,
+    ) -> Result<Option<Self>, HttpRequestError> {
+        let client_certificate = buf_reader.get_ref().client_certificate();
+
+        Self::from_reader(buf_reader, client_certificate).await
+    }
+
+    pub async fn from_reader(
+        buf_reader: &mut (impl AsyncBufRead + Unpin),
+        client_certificate: Option<ClientCertificate>,
+    ) -> Result<Option<Self>, HttpRequestError> {
+        let mut buf = BytesMut::new();
+        let mut request_head_parser = RequestHeadParser::new();
+        let (request_line, http_headers) = loop {
+            let available = buf_reader.fill_buf().await?;
+            if available.is_empty() {
+                if buf.is_empty() {
+                    return Ok(None);
+                }
+
+                return Err(HttpRequestError::Malformed(String::from(
+                    "connection closed before the end of the headers",
+                )));
+            }
+            let buffered = buf.len();
+            buf.extend_from_slice(available);
+
+            let parsed = request_head_parser.parse(&buf)?;
+            let head_size = parsed.as_ref().map_or(buf.len(), |(_, consumed)| *consumed);
+            if head_size > MAX_REQUEST_HEAD_SIZE {
+                return Err(HttpRequestError::HeadTooLarge(MAX_REQUEST_HEAD_SIZE));
+            }
+            if let Some((request_head, consumed)) = parsed {
+                let request_line = RequestLine::try_new(
+                    request_head.http_method,
+                    request_head.request_target,
+                    request_head.http_version,
+                )?;
+                let mut http_headers = HttpHeaders::new();
+                for (name, value) in &request_head.fields {
+                    let http_header = HttpHeader::try_new(name, value)
+                        .map_err(|e| HttpRequestError::Malformed(e.to_string()))?;
+                    http_headers.add(http_header);
+                }
+                buf_reader.consume(consumed - buffered);
+                if http_headers.has_conflicting_content_lengths() {
+                    return Err(HttpRequestError::Malformed(String::from(
+                        "conflicting Content-Length headers",
+                    )));
+                }
+
+                break (request_line, http_headers);
+            }
+            buf_reader.consume(buf.len() - buffered);
         };
 
-        Self {
+        Ok(Some(Self {
             request_line,
             http_headers,
-            body,
-        }
+            client_certificate,
+            principal: None,
+        }))
     }
 
     pub fn http_method(&self) -> &HttpMethod {
         &self.request_line.http_method
     }
 
-    pub fn body(&self) -> Option<&String> {
-        self.body.as_ref()
+    pub fn http_version(&self) -> HttpVersion {
+        self.request_line.http_version
+    }
+
+    pub fn keep_alive(&self) -> bool {
+        let mut connection_options = vec![];
+        for http_header in &self.http_headers {
+            if let HttpHeader::Connection(connection) = http_header {
+                connection_options.extend(connection.tokens());
+            }
+        }
+        let has_option = |option: &str| {
+            connection_options
+                .iter()
+                .any(|connection_option| connection_option.eq_ignore_ascii_case(option))
+        };
+
+        match self.http_version() {
+            HttpVersion::Http10 => has_option("keep-alive"),
+            HttpVersion::Http11 => !has_option("close"),
+        }
+    }
+
+    pub fn expects_continue(&self) -> bool {
+        self.http_version() == HttpVersion::Http11
+            && self
+                .expectations()
+                .iter()
+                .any(|expectation| expectation.eq_ignore_ascii_case("100-continue"))
+    }
+
+    pub fn has_unsupported_expectation(&self) -> bool {
+        self.http_version() == HttpVersion::Http11
+            && self
+                .expectations()
+                .iter()
+                .any(|expectation| !expectation.eq_ignore_ascii_case("100-continue"))
+    }
+
+    fn expectations(&self) -> Vec<&String> {
+        let mut expectations = vec![];
+        for http_header in &self.http_headers {
+            if let HttpHeader::Expect(expect) = http_header {
+                expectations.extend(expect.tokens());
+            }
+        }
+
+        expectations
+    }
+
+    pub fn is_chunked(&self) -> bool {
+        self.http_version() == HttpVersion::Http11
+            && matches!(
+                self.transfer_codings().as_slice(),
+                [transfer_coding] if transfer_coding.eq_ignore_ascii_case("chunked")
+            )
+    }
+
+    pub fn has_unsupported_transfer_encoding(&self) -> bool {
+        self.has_transfer_encoding() && !self.is_chunked()
+    }
+
+    pub fn has_ambiguous_length(&self) -> bool {
+        self.has_transfer_encoding() && self.http_headers.content_length().is_some()
+    }
+
+    fn has_transfer_encoding(&self) -> bool {
+        (&self.http_headers)
+            .into_iter()
+            .any(|http_header| matches!(http_header, HttpHeader::TransferEncoding(_)))
+    }
+
+    fn transfer_codings(&self) -> Vec<&String> {
+        let mut transfer_codings = vec![];
+        for http_header in &self.http_headers {
+            if let HttpHeader::TransferEncoding(transfer_encoding) = http_header {
+                transfer_codings.extend(transfer_encoding.tokens());
+            }
+        }
+
+        transfer_codings
+    }
+
+    pub fn host(&self) -> Option<&Host> {
+        for http_header in &self.http_headers {
+            if let HttpHeader::Host(host) = http_header {
+                return Some(host);
+            }
+        }
+
+        None
+    }
+
+    pub fn has_valid_host(&self) -> bool {
+        let hosts = (&self.http_headers)
+            .into_iter()
+            .filter(|http_header| matches!(http_header, HttpHeader::Host(_)))
+            .count();
+
+        match (hosts, self.host()) {
+            (0, _) => self.http_version() == HttpVersion::Http10,
+            (1, Some(host)) => host.is_valid(),
+            _ => false,
+        }
+    }
+
+    pub fn content_length(&self) -> Option<usize> {
+        if self.has_transfer_encoding() {
+            return None;
+        }
+
+        self.http_headers.content_length()
+    }
+
+    pub async fn read_form(
+        &self,
+        body: &mut (impl AsyncRead + Unpin),
+        max_body_size: usize,
+    ) -> Result<QueryParameters, BodyError> {
+        self.check_content_type("application", "x-www-form-urlencoded")?;
+        let body = self.read_body(body, max_body_size).await?;
+        let body = std::str::from_utf8(&body)
+            .map_err(|e| BodyError::Malformed(format!("form body is not valid UTF-8: {e}")))?;
+
+        Ok(QueryParameters::from(body))
+    }
+
+    pub async fn read_json<T: DeserializeOwned>(
+        &self,
+        body: &mut (impl AsyncRead + Unpin),
+        max_body_size: usize,
+    ) -> Result<T, BodyError> {
+        self.check_content_type("application", "json")?;
+        let body = self.read_body(body, max_body_size).await?;
+
+        serde_json::from_slice(&body)
+            .map_err(|e| BodyError::Malformed(format!("JSON body is invalid: {e}")))
+    }
+
+    fn check_content_type(&self, media_type: &str, subtype: &str) -> Result<(), BodyError> {
+        let content_type = self
+            .content_type()
+            .ok_or_else(|| BodyError::UnsupportedMediaType(String::from("none")))?;
+        if content_type.media_type() != media_type || content_type.subtype() != subtype {
+            return Err(BodyError::UnsupportedMediaType(format!(
+                "{}/{}",
+                content_type.media_type(),
+                content_type.subtype()
+            )));
+        }
+        match content_type.charset() {
+            Some(charset)
+                if !charset.eq_ignore_ascii_case("utf-8")
+                    && !charset.eq_ignore_ascii_case("us-ascii") =>
+            {
+                Err(BodyError::UnsupportedMediaType(format!(
+                    "{}/{}; charset={}",
+                    media_type, subtype, charset
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn read_body(
+        &self,
+        body: &mut (impl AsyncRead + Unpin),
+        max_body_size: usize,
+    ) -> Result<Vec<u8>, BodyError> {
+        if !self.is_chunked() {
+            let content_length = self.content_length().unwrap_or_default();
+            if content_length > max_body_size {
+                return Err(BodyError::TooLarge(max_body_size));
+            }
+            let mut buf = vec![0; content_length];
+            body.read_exact(&mut buf).await.map_err(BodyError::Io)?;
+
+            return Ok(buf);
+        }
+
+        let mut buf = vec![];
+        body.take(max_body_size as u64 + 1)
+            .read_to_end(&mut buf)
+            .await
+            .map_err(BodyError::Io)?;
+        if buf.len() > max_body_size {
+            return Err(BodyError::TooLarge(max_body_size));
+        }
+
+        Ok(buf)
+    }
+
+    pub fn client_certificate(&self) -> Option<&ClientCertificate> {
+        self.client_certificate.as_ref()
+    }
+
+    pub fn set_principal(&mut self, principal: Principal) {
+        self.principal = Some(principal);
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.principal.is_some()
+    }
+
+    pub fn user_id(&self) -> Option<&str> {
+        match &self.principal {
+            Some(Principal::User(user_id)) => Some(user_id),
+            _ => None,
+        }
+    }
+
+    pub fn claims(&self) -> Option<&Claims> {
+        match &self.principal {
+            Some(Principal::Jwt(claims)) => Some(claims),
+            _ => None,
+        }
     }
 }
 
 #[derive(Debug)]
 pub struct HttpMethodError;
 
+#[derive(Debug)]
+pub enum HttpRequestError {
+    Io(std::io::Error),
+    Parse(ParseError),
+    Malformed(String),
+    HeadTooLarge(usize),
+    UnsupportedMethod(String),
+    UnsupportedVersion(String),
+}
+
+impl HttpRequestError {
+    pub fn http_response_code(&self) -> HttpResponseCode {
+        match self {
+            Self::Io(_) | Self::Parse(_) | Self::Malformed(_) => HttpResponseCode::BadRequest,
+            Self::HeadTooLarge(_) => HttpResponseCode::RequestHeaderFieldsTooLarge,
+            Self::UnsupportedMethod(_) => HttpResponseCode::NotImplemented,
+            Self::UnsupportedVersion(_) => HttpResponseCode::HttpVersionNotSupported,
+        }
+    }
+}
+
+impl From<std::io::Error> for HttpRequestError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParseError> for HttpRequestError {
+    fn from(value: ParseError) -> Self {
+        Self::Parse(value)
+    }
+}
+
+impl Display for HttpRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(io_err) => write!(f, "{}", io_err),
+            Self::Parse(parse_err) => write!(f, "{}", parse_err),
+            Self::Malformed(reason) => write!(f, "Invalid request: {}", reason),
+            Self::HeadTooLarge(max_head_size) => {
+                write!(f, "Request head is larger than {} bytes", max_head_size)
+            }
+            Self::UnsupportedMethod(http_method) => {
+                write!(f, "Unsupported HTTP method: {}", http_method)
+            }
+            Self::UnsupportedVersion(http_version) => {
+                write!(f, "Unsupported HTTP version: {}", http_version)
+            }
+        }
+    }
+}
+
+impl Error for HttpRequestError {}
+
+#[derive(Debug)]
+pub enum BodyError {
+    UnsupportedMediaType(String),
+    TooLarge(usize),
+    Malformed(String),
+    Io(std::io::Error),
+}
+
+impl BodyError {
+    pub fn http_response_code(&self) -> HttpResponseCode {
+        match self {
+            Self::UnsupportedMediaType(_) => HttpResponseCode::UnsupportedMediaType,
+            Self::TooLarge(_) => HttpResponseCode::ContentTooLarge,
+            Self::Malformed(_) => HttpResponseCode::BadRequest,
+            Self::Io(_) => HttpResponseCode::BadRequest,
+        }
+    }
+}
+
+impl Display for BodyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedMediaType(content_type) => {
+                write!(f, "Unsupported request body type: {}", content_type)
+            }
+            Self::TooLarge(max_body_size) => {
+                write!(f, "Request body is larger than {} bytes", max_body_size)
+            }
+            Self::Malformed(reason) => write!(f, "Invalid request body: {}", reason),
+            Self::Io(io_err) => write!(f, "Could not read request body: {}", io_err),
+        }
+    }
+}
+
+impl Error for BodyError {}
+
 struct RequestLine {
-    request_target: String,
+    request_target: RequestTarget,
     http_method: HttpMethod,
+    http_version: HttpVersion,
 }
 
-impl From<&str> for RequestLine {
-    fn from(value: &str) -> Self {
-        let mut request_line_parts = value.split(' ');
-
-        let http_method = request_line_parts
-            .next()
-            .expect("Request line should have a HTTP method");
+impl RequestLine {
+    fn try_new(
+        http_method: &str,
+        request_target: &str,
+        http_version: Option<&str>,
+    ) -> Result<Self, HttpRequestError> {
+        let http_version = match http_version {
+            Some(http_version) => HttpVersion::try_from(http_version)?,
+            None => {
+                return Err(HttpRequestError::UnsupportedVersion(String::from(
+                    "HTTP/0.9",
+                )))
+            }
+        };
         let http_method = HttpMethod::try_from(http_method)
-            .expect("Request line should have a correct HTTP method");
+            .map_err(|_| HttpRequestError::UnsupportedMethod(http_method.to_string()))?;
 
-        let request_target = request_line_parts
-            .next()
-            .expect("Request line should have a request target");
-        let request_target = request_target.to_string();
+        let request_target = RequestTarget::try_from(request_target).map_err(|_| {
+            HttpRequestError::Malformed(format!("bad request target {}", request_target))
+        })?;
 
-        Self {
+        Ok(Self {
             request_target,
             http_method,
-        }
+            http_version,
+        })
     }
 }
 
@@ -142,6 +725,17 @@ impl HttpHeaders {
         self.0.push(http_header);
     }
 
+    fn has_conflicting_content_lengths(&self) -> bool {
+        !self
+            .0
+            .iter()
+            .filter_map(|http_header| match http_header {
+                HttpHeader::ContentLength(content_length) => Some(content_length.value()),
+                _ => None,
+            })
+            .all_equal()
+    }
+
     fn content_length(&self) -> Option<usize> {
         let mut content_length_value = None;
         for http_header in &self.0 {
@@ -1,74 +1,1262 @@
-use std::path::PathBuf;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use itertools::Itertools;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+use serde_json::json;
 
 use crate::{
-    http_header::{ContentLength, ContentType, HttpHeader, UserAgent},
-    http_response::{HttpResponse, HttpResponseCode},
+    auth::Authenticator,
+    digest::{DigestAlgorithm, DigestCache, DigestVerifier},
+    directory_listing::{escape_html, DirectoryListing},
+    error_page::ErrorPageFormat,
+    file_lock::FileLocks,
+    file_validators::{EntityTagMode, FileValidators},
+    http_header::{
+        Authorization, ByteRangeSpec, ContentLength, ContentRange, ContentType, EntityTagMatch,
+        HttpHeader, Location, Range, TokenList, UserAgent, WwwAuthenticate,
+    },
+    http_request::{BodyError, HttpRequest},
+    http_response::{FileBody, HttpResponse, HttpResponseCode},
+    jwt::Claims,
+    mime::MimeTypes,
+    multipart::{sanitize_filename, MultipartError, MultipartLimits, MultipartReader},
+    request_target::has_path_prefix,
+    session::{Session, USER_KEY},
 };
 
+const UPLOAD_FORM: &str = "<!DOCTYPE html>
+<html>
+<head><title>Upload files</title></head>
+<body>
+<h1>Upload files</h1>
+<form method=\"post\" action=\"/upload\" enctype=\"multipart/form-data\">
+<input type=\"file\" name=\"file\" multiple>
+<input type=\"submit\" value=\"Upload\">
+</form>
+</body>
+</html>
+";
+const MAX_FIELD_SIZE: usize = 64 * 1024;
+
 pub fn handle_get_root() -> HttpResponse {
     HttpResponse::new(HttpResponseCode::Ok, None, None)
 }
 
-pub fn handle_get_echo(echo: String) -> HttpResponse {
-    let content_length = echo.len();
-
-    HttpResponse::new(
-        HttpResponseCode::Ok,
-        Some(vec![
-            HttpHeader::ContentType(ContentType::TextPlain),
-            HttpHeader::ContentLength(ContentLength::new(content_length)),
-        ]),
-        Some(echo),
+pub fn handle_get_echo(echo: String, http_request: &HttpRequest) -> HttpResponse {
+    handle_negotiated(
+        http_request,
+        &[ContentType::text_plain(), ContentType::application_json()],
+        |content_type| match content_type.subtype() {
+            "json" => json!({ "echo": echo }).to_string(),
+            _ => echo,
+        },
     )
 }
 
+fn handle_negotiated(
+    http_request: &HttpRequest,
+    offers: &[ContentType],
+    render: impl FnOnce(&ContentType) -> String,
+) -> HttpResponse {
+    let mut http_response = match http_request.negotiate(offers) {
+        Some(content_type) => {
+            let mut http_response = HttpResponse::new(HttpResponseCode::Ok, None, None);
+            http_response.set_body(content_type.clone(), render(content_type));
+
+            http_response
+        }
+        None => HttpResponse::new(HttpResponseCode::NotAcceptable, None, None),
+    };
+    http_response.add_header(HttpHeader::Vary(TokenList::from("Accept")));
+
+    http_response
+}
+
 pub fn handle_not_found() -> HttpResponse {
     HttpResponse::new(HttpResponseCode::NotFound, None, None)
 }
 
-pub fn handle_get_user_agent(user_agent: &UserAgent) -> HttpResponse {
-    let user_agent_value = user_agent.value().to_string();
-    let content_length = user_agent_value.len();
+pub fn handle_bad_request() -> HttpResponse {
+    HttpResponse::new(HttpResponseCode::BadRequest, None, None)
+}
+
+pub fn handle_expectation_failed() -> HttpResponse {
+    HttpResponse::new(HttpResponseCode::ExpectationFailed, None, None)
+}
+
+pub fn handle_not_implemented() -> HttpResponse {
+    HttpResponse::new(HttpResponseCode::NotImplemented, None, None)
+}
+
+pub fn handle_service_unavailable() -> HttpResponse {
+    HttpResponse::new(HttpResponseCode::ServiceUnavailable, None, None)
+}
+
+pub fn handle_forbidden() -> HttpResponse {
+    HttpResponse::new(HttpResponseCode::Forbidden, None, None)
+}
+
+pub fn handle_unauthorized(www_authenticates: Vec<WwwAuthenticate>) -> HttpResponse {
+    HttpResponse::new(
+        HttpResponseCode::Unauthorized,
+        Some(
+            www_authenticates
+                .into_iter()
+                .map(HttpHeader::WwwAuthenticate)
+                .collect(),
+        ),
+        None,
+    )
+}
+
+pub fn handle_options(preflight_headers: Option<Vec<HttpHeader>>) -> HttpResponse {
+    let http_headers = preflight_headers.unwrap_or_else(|| {
+        vec![HttpHeader::Allow(TokenList::from(
+            "GET, POST, PUT, OPTIONS",
+        ))]
+    });
+
+    HttpResponse::new(HttpResponseCode::NoContent, Some(http_headers), None)
+}
+
+pub fn handle_get_session(session: Option<&Session>) -> HttpResponse {
+    match session {
+        Some(session) => handle_session_values(session),
+        None => handle_not_found(),
+    }
+}
+
+pub async fn handle_post_session(
+    body: &mut (impl AsyncRead + Unpin),
+    http_request: &HttpRequest,
+    session: Option<&mut Session>,
+    max_body_size: usize,
+) -> HttpResponse {
+    let Some(session) = session else {
+        return handle_not_found();
+    };
+    let form = match http_request.read_form(body, max_body_size).await {
+        Ok(form) => form,
+        Err(e) => return handle_body_error(e),
+    };
+    if form.iter().any(|(name, _)| name == USER_KEY) {
+        return handle_forbidden();
+    }
+    for (name, value) in form.iter() {
+        session.set(name, value);
+    }
+
+    handle_session_values(session)
+}
+
+pub fn handle_post_session_login(
+    http_request: &HttpRequest,
+    session: Option<&mut Session>,
+    www_authenticates: Vec<WwwAuthenticate>,
+) -> HttpResponse {
+    let Some(session) = session else {
+        return handle_not_found();
+    };
+    let user_id = match http_request.authorization() {
+        Some(Authorization::Basic(_)) => http_request.user_id(),
+        _ => None,
+    };
+    let Some(user_id) = user_id else {
+        return handle_unauthorized(www_authenticates);
+    };
+    session.log_in(user_id);
+
+    handle_session_values(session)
+}
+
+pub fn handle_post_session_logout(session: Option<&mut Session>) -> HttpResponse {
+    let Some(session) = session else {
+        return handle_not_found();
+    };
+    session.destroy();
+
+    HttpResponse::new(HttpResponseCode::NoContent, None, None)
+}
+
+fn handle_session_values(session: &Session) -> HttpResponse {
+    let values = json!({ "session": session.values() }).to_string();
 
     HttpResponse::new(
         HttpResponseCode::Ok,
         Some(vec![
-            HttpHeader::ContentType(ContentType::TextPlain),
-            HttpHeader::ContentLength(ContentLength::new(content_length)),
+            HttpHeader::ContentType(ContentType::application_json()),
+            HttpHeader::ContentLength(ContentLength::new(values.len())),
         ]),
-        Some(user_agent_value),
+        Some(Bytes::from(values)),
     )
 }
 
-pub async fn handle_get_files(file: String, directory: &PathBuf) -> HttpResponse {
-    let mut file_path = PathBuf::from(directory);
-    file_path.push(file);
-
-    if file_path.exists() {
-        let file_contents = tokio::fs::read_to_string(file_path)
+pub async fn handle_post_echo(
+    body: &mut (impl AsyncRead + Unpin),
+    http_request: &HttpRequest,
+    max_body_size: usize,
+) -> HttpResponse {
+    let is_json = http_request.content_type().is_some_and(|content_type| {
+        content_type.media_type() == "application" && content_type.subtype() == "json"
+    });
+    let echo = if is_json {
+        http_request
+            .read_json::<serde_json::Value>(body, max_body_size)
             .await
-            .expect("Can read file contents");
-        let content_length = file_contents.len();
+    } else {
+        http_request
+            .read_form(body, max_body_size)
+            .await
+            .map(|form| {
+                form.iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value }))
+                    .collect()
+            })
+    };
+
+    match echo {
+        Ok(echo) => {
+            let echo = echo.to_string();
+
+            HttpResponse::new(
+                HttpResponseCode::Ok,
+                Some(vec![
+                    HttpHeader::ContentType(ContentType::application_json()),
+                    HttpHeader::ContentLength(ContentLength::new(echo.len())),
+                ]),
+                Some(Bytes::from(echo)),
+            )
+        }
+        Err(e) => handle_body_error(e),
+    }
+}
+
+fn handle_body_error(body_error: BodyError) -> HttpResponse {
+    println!("error: {body_error}");
+
+    HttpResponse::new(body_error.http_response_code(), None, None)
+}
+
+pub fn handle_get_user_agent(user_agent: &UserAgent, http_request: &HttpRequest) -> HttpResponse {
+    let user_agent = user_agent.value().to_string();
+
+    handle_negotiated(
+        http_request,
+        &[ContentType::text_plain(), ContentType::application_json()],
+        |content_type| match content_type.subtype() {
+            "json" => json!({ "user_agent": user_agent }).to_string(),
+            _ => user_agent,
+        },
+    )
+}
+
+pub struct FileOptions<'a> {
+    pub entity_tag_mode: EntityTagMode,
+    pub mime_types: &'a MimeTypes,
+    pub directory_listing: Option<&'a DirectoryListing>,
+    pub spa_fallback: Option<&'a str>,
+    pub digest_cache: &'a DigestCache,
+}
 
-        HttpResponse::new(
-            HttpResponseCode::Ok,
+pub async fn handle_get_files(
+    file: String,
+    directory: Option<&Path>,
+    http_request: &HttpRequest,
+    file_options: FileOptions<'_>,
+) -> HttpResponse {
+    let Some(directory) = directory else {
+        return handle_not_found();
+    };
+    let Some(mut file_path) = resolve_file_path(directory, &file) else {
+        return handle_not_found();
+    };
+
+    if file_path.is_dir() {
+        if !http_request.path().ends_with('/') {
+            return handle_directory_redirect(http_request);
+        }
+        let index_path = file_path.join("index.html");
+        if !index_path.is_file() {
+            return match file_options.directory_listing {
+                Some(directory_listing) => {
+                    handle_directory_listing(&file_path, &file, http_request, directory_listing)
+                        .await
+                }
+                None => handle_forbidden(),
+            };
+        }
+
+        file_path = index_path;
+    } else if !file_path.exists() {
+        match file_options.spa_fallback {
+            Some(spa_fallback) if !is_asset_path(&file) => file_path = directory.join(spa_fallback),
+            _ => return handle_not_found(),
+        }
+    }
+
+    if !file_path.is_file() {
+        return handle_not_found();
+    }
+
+    match handle_file(&file_path, http_request, &file_options).await {
+        Ok(http_response) => http_response,
+        Err(e) => handle_io_error(e),
+    }
+}
+
+async fn handle_file(
+    file_path: &Path,
+    http_request: &HttpRequest,
+    file_options: &FileOptions<'_>,
+) -> std::io::Result<HttpResponse> {
+    let metadata = tokio::fs::metadata(file_path).await?;
+    let file_validators = FileValidators::for_file(
+        file_path,
+        &metadata,
+        file_options.entity_tag_mode,
+        file_options.digest_cache,
+    )
+    .await?;
+    let mut file = tokio::fs::File::open(file_path).await?;
+    let mut file_head = vec![0; 1024];
+    let file_head_len = file.read(&mut file_head).await?;
+    let content_type = file_options
+        .mime_types
+        .content_type(file_path, &file_head[..file_head_len]);
+    let file = file.into_std().await;
+
+    let not_modified = match (
+        http_request.if_none_match(),
+        http_request.if_modified_since(),
+    ) {
+        (Some(if_none_match), _) => if_none_match.weak_matches(file_validators.entity_tag()),
+        (None, Some(if_modified_since)) => file_validators.last_modified() <= if_modified_since,
+        (None, None) => false,
+    };
+    let range_applies = match http_request.if_range() {
+        Some(if_range) => if_range.matches(
+            file_validators.entity_tag(),
+            file_validators.last_modified(),
+        ),
+        None => true,
+    };
+
+    let mut http_response = match http_request.range() {
+        _ if not_modified => HttpResponse::new(HttpResponseCode::NotModified, None, None),
+        Some(Range::Bytes(byte_range_specs)) if range_applies => {
+            handle_partial_file(file, metadata.len(), content_type, byte_range_specs)
+        }
+        _ => {
+            let mut file_body = FileBody::new(file);
+            file_body.push_range(0, metadata.len());
+
+            HttpResponse::from_file(
+                HttpResponseCode::Ok,
+                Some(vec![
+                    HttpHeader::ContentType(content_type),
+                    HttpHeader::ContentLength(ContentLength::new(file_body.len() as usize)),
+                    HttpHeader::AcceptRanges(TokenList::from("bytes")),
+                ]),
+                file_body,
+            )
+        }
+    };
+    http_response.add_header(HttpHeader::ETag(file_validators.entity_tag().clone()));
+    http_response.add_header(HttpHeader::LastModified(*file_validators.last_modified()));
+    http_response.add_header(HttpHeader::XContentTypeOptions(TokenList::from("nosniff")));
+    let want_repr_digest = http_request
+        .want_repr_digest()
+        .and_then(DigestAlgorithm::preferred);
+    if let Some(algorithm) = want_repr_digest {
+        let repr_digest = file_options
+            .digest_cache
+            .digest_fields(file_path, &metadata, algorithm)
+            .await?;
+        http_response.add_header(HttpHeader::ReprDigest(repr_digest));
+    }
+    let want_content_digest = http_request
+        .want_content_digest()
+        .and_then(DigestAlgorithm::preferred)
+        .filter(|_| matches!(http_response.http_response_code(), HttpResponseCode::Ok));
+    if let Some(algorithm) = want_content_digest {
+        let content_digest = file_options
+            .digest_cache
+            .digest_fields(file_path, &metadata, algorithm)
+            .await?;
+        http_response.add_header(HttpHeader::ContentDigest(content_digest));
+    }
+
+    Ok(http_response)
+}
+
+fn handle_directory_redirect(http_request: &HttpRequest) -> HttpResponse {
+    let location = match http_request.query() {
+        Some(query) => format!("{}/?{}", http_request.path(), query),
+        None => format!("{}/", http_request.path()),
+    };
+
+    HttpResponse::new(
+        HttpResponseCode::MovedPermanently,
+        Some(vec![HttpHeader::Location(Location::new(location))]),
+        None,
+    )
+}
+
+async fn handle_directory_listing(
+    directory: &Path,
+    file: &str,
+    http_request: &HttpRequest,
+    directory_listing: &DirectoryListing,
+) -> HttpResponse {
+    let listing = directory_listing
+        .render(
+            directory,
+            http_request.path(),
+            !file.trim_matches('/').is_empty(),
+            http_request.query_parameters(),
+            http_request.accept(),
+        )
+        .await;
+
+    match listing {
+        Ok((content_type, body)) => {
+            let mut http_response = HttpResponse::new(
+                HttpResponseCode::Ok,
+                Some(vec![HttpHeader::Vary(TokenList::from("Accept"))]),
+                None,
+            );
+            http_response.set_body(content_type, body);
+
+            http_response
+        }
+        Err(e) => handle_io_error(e),
+    }
+}
+
+fn handle_partial_file(
+    file: File,
+    complete_length: u64,
+    content_type: ContentType,
+    byte_range_specs: &[ByteRangeSpec],
+) -> HttpResponse {
+    let ranges = byte_range_specs
+        .iter()
+        .filter_map(|byte_range_spec| byte_range_spec.resolve(complete_length))
+        .sorted_unstable()
+        .coalesce(|(first, last), (next_first, next_last)| {
+            if next_first <= last.saturating_add(1) {
+                Ok((first, last.max(next_last)))
+            } else {
+                Err(((first, last), (next_first, next_last)))
+            }
+        })
+        .collect_vec();
+
+    match ranges[..] {
+        [] => HttpResponse::new(
+            HttpResponseCode::RangeNotSatisfiable,
             Some(vec![
-                HttpHeader::ContentType(ContentType::ApplicationOctetStream),
-                HttpHeader::ContentLength(ContentLength::new(content_length)),
+                HttpHeader::AcceptRanges(TokenList::from("bytes")),
+                HttpHeader::ContentRange(ContentRange::Unsatisfied { complete_length }),
             ]),
-            Some(file_contents),
+            None,
+        ),
+        [(first, last)] => {
+            let mut file_body = FileBody::new(file);
+            file_body.push_range(first, last - first + 1);
+
+            HttpResponse::from_file(
+                HttpResponseCode::PartialContent,
+                Some(vec![
+                    HttpHeader::ContentType(content_type),
+                    HttpHeader::ContentLength(ContentLength::new(file_body.len() as usize)),
+                    HttpHeader::AcceptRanges(TokenList::from("bytes")),
+                    HttpHeader::ContentRange(ContentRange::Satisfied {
+                        first,
+                        last,
+                        complete_length,
+                    }),
+                ]),
+                file_body,
+            )
+        }
+        _ => {
+            let boundary = format!(
+                "{:x}",
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos()
+            );
+            let mut file_body = FileBody::new(file);
+            for (first, last) in ranges {
+                let content_range = ContentRange::Satisfied {
+                    first,
+                    last,
+                    complete_length,
+                };
+                file_body.push_bytes(format!(
+                    "--{}\r\n{}\r\n{}\r\n\r\n",
+                    boundary,
+                    HttpHeader::ContentType(content_type.clone()),
+                    HttpHeader::ContentRange(content_range),
+                ));
+                file_body.push_range(first, last - first + 1);
+                file_body.push_bytes("\r\n");
+            }
+            file_body.push_bytes(format!("--{}--\r\n", boundary));
+
+            HttpResponse::from_file(
+                HttpResponseCode::PartialContent,
+                Some(vec![
+                    HttpHeader::ContentType(ContentType::multipart_byteranges(&boundary)),
+                    HttpHeader::ContentLength(ContentLength::new(file_body.len() as usize)),
+                    HttpHeader::AcceptRanges(TokenList::from("bytes")),
+                ]),
+                file_body,
+            )
+        }
+    }
+}
+
+pub struct UploadOptions<'a> {
+    pub entity_tag_mode: EntityTagMode,
+    pub digest_cache: &'a DigestCache,
+    pub file_locks: &'a FileLocks,
+    pub fsync: bool,
+}
+
+pub async fn handle_post_files(
+    file: String,
+    directory: Option<&Path>,
+    body: &mut (impl AsyncRead + Unpin),
+    http_request: &HttpRequest,
+    upload_options: UploadOptions<'_>,
+) -> HttpResponse {
+    let Some(directory) = directory else {
+        return handle_not_found();
+    };
+    let upload = Upload {
+        http_request,
+        upload_options,
+        replaced_response_code: HttpResponseCode::Created,
+    };
+
+    write_file(file, directory, body, upload).await
+}
+
+pub async fn handle_put_files(
+    file: String,
+    directory: Option<&Path>,
+    body: &mut (impl AsyncRead + Unpin),
+    http_request: &HttpRequest,
+    upload_options: UploadOptions<'_>,
+) -> HttpResponse {
+    let Some(directory) = directory else {
+        return handle_not_found();
+    };
+    let upload = Upload {
+        http_request,
+        upload_options,
+        replaced_response_code: HttpResponseCode::NoContent,
+    };
+
+    write_file(file, directory, body, upload).await
+}
+
+struct Upload<'a> {
+    http_request: &'a HttpRequest,
+    upload_options: UploadOptions<'a>,
+    replaced_response_code: HttpResponseCode,
+}
+
+impl Upload<'_> {
+    async fn current_file_validators(
+        &self,
+        file_path: &Path,
+    ) -> std::io::Result<Option<FileValidators>> {
+        match self.file_validators(file_path).await {
+            Ok(file_validators) => Ok(Some(file_validators)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn file_validators(&self, file_path: &Path) -> std::io::Result<FileValidators> {
+        let metadata = tokio::fs::metadata(file_path).await?;
+
+        FileValidators::for_file(
+            file_path,
+            &metadata,
+            self.upload_options.entity_tag_mode,
+            self.upload_options.digest_cache,
         )
+        .await
+    }
+
+    fn precondition_failed(&self, current_file_validators: Option<&FileValidators>) -> bool {
+        let if_match_failed = match (self.http_request.if_match(), current_file_validators) {
+            (Some(if_match), Some(file_validators)) => {
+                !if_match.strong_matches(file_validators.entity_tag())
+            }
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        let if_none_match_failed =
+            match (self.http_request.if_none_match(), current_file_validators) {
+                (Some(if_none_match), Some(file_validators)) => {
+                    if_none_match.weak_matches(file_validators.entity_tag())
+                }
+                (_, None) | (None, _) => false,
+            };
+
+        if_match_failed || if_none_match_failed
+    }
+
+    fn is_create_only(&self) -> bool {
+        matches!(self.http_request.if_none_match(), Some(EntityTagMatch::Any))
+    }
+}
+
+async fn write_file(
+    file: String,
+    directory: &Path,
+    body: &mut (impl AsyncRead + Unpin),
+    upload: Upload<'_>,
+) -> HttpResponse {
+    let upload_prefix = upload.http_request.claims().and_then(Claims::upload_prefix);
+    if let Some(upload_prefix) = upload_prefix {
+        if !is_within_upload_prefix(&file, upload_prefix) {
+            return handle_forbidden();
+        }
+    }
+
+    let Some(file_path) = resolve_file_path(directory, &file) else {
+        return handle_not_found();
+    };
+    let content_length = upload.http_request.content_length();
+    if content_length.is_none() && !upload.http_request.is_chunked() {
+        return HttpResponse::new(HttpResponseCode::LengthRequired, None, None);
+    }
+
+    let current_file_validators = match upload.current_file_validators(&file_path).await {
+        Ok(current_file_validators) => current_file_validators,
+        Err(e) => return handle_io_error(e),
+    };
+    if upload.precondition_failed(current_file_validators.as_ref()) {
+        return HttpResponse::new(HttpResponseCode::PreconditionFailed, None, None);
+    }
+
+    let temp_file_path = temp_file_path(&file_path);
+    let mut digest_verifier = DigestVerifier::from_request(upload.http_request);
+    let written = write_temp_file(
+        &temp_file_path,
+        body,
+        content_length,
+        &mut digest_verifier,
+        upload.upload_options.fsync,
+    )
+    .await;
+    match written {
+        Ok(written)
+            if content_length.is_none_or(|content_length| written == content_length as u64)
+                && digest_verifier.verify() => {}
+        Ok(_) => {
+            remove_temp_file(&temp_file_path).await;
+
+            return HttpResponse::new(HttpResponseCode::BadRequest, None, None);
+        }
+        Err(e) if is_malformed_body(&e) => {
+            println!("error: {e}");
+            remove_temp_file(&temp_file_path).await;
+
+            return HttpResponse::new(HttpResponseCode::BadRequest, None, None);
+        }
+        Err(e) => {
+            println!("error: {e}");
+            remove_temp_file(&temp_file_path).await;
+
+            return HttpResponse::new(HttpResponseCode::InternalServerError, None, None);
+        }
+    }
+
+    let file_lock = upload.upload_options.file_locks.lock(&file_path).await;
+    let current_file_validators = match upload.current_file_validators(&file_path).await {
+        Ok(current_file_validators) => current_file_validators,
+        Err(e) => {
+            remove_temp_file(&temp_file_path).await;
+
+            return handle_io_error(e);
+        }
+    };
+    if upload.precondition_failed(current_file_validators.as_ref()) {
+        remove_temp_file(&temp_file_path).await;
+
+        return HttpResponse::new(HttpResponseCode::PreconditionFailed, None, None);
+    }
+    let committed = if upload.is_create_only() {
+        link_temp_file(&temp_file_path, &file_path, upload.upload_options.fsync).await
     } else {
-        HttpResponse::new(HttpResponseCode::NotFound, None, None)
+        rename_temp_file(&temp_file_path, &file_path, upload.upload_options.fsync).await
+    };
+    match committed {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            remove_temp_file(&temp_file_path).await;
+
+            return HttpResponse::new(HttpResponseCode::PreconditionFailed, None, None);
+        }
+        Err(e) => {
+            println!("error: {e}");
+            remove_temp_file(&temp_file_path).await;
+
+            return HttpResponse::new(HttpResponseCode::InternalServerError, None, None);
+        }
+    }
+
+    let file_validators = upload.file_validators(&file_path).await;
+    drop(file_lock);
+    let file_validators = match file_validators {
+        Ok(file_validators) => file_validators,
+        Err(e) => return handle_io_error(e),
+    };
+    let http_response_code = match current_file_validators {
+        Some(_) => upload.replaced_response_code,
+        None => HttpResponseCode::Created,
+    };
+
+    HttpResponse::new(
+        http_response_code,
+        Some(vec![
+            HttpHeader::ETag(file_validators.entity_tag().clone()),
+            HttpHeader::LastModified(*file_validators.last_modified()),
+        ]),
+        None,
+    )
+}
+
+fn temp_file_path(file_path: &Path) -> PathBuf {
+    let file_name = file_path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    file_path.with_file_name(format!(".{}.{:x}.upload", file_name, nanos))
+}
+
+async fn write_temp_file(
+    temp_file_path: &Path,
+    body: &mut (impl AsyncRead + Unpin),
+    content_length: Option<usize>,
+    digest_verifier: &mut DigestVerifier,
+    fsync: bool,
+) -> std::io::Result<u64> {
+    let mut temp_file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_file_path)
+        .await?;
+    let mut body =
+        body.take(content_length.map_or(u64::MAX, |content_length| content_length as u64));
+    let mut buf = vec![0; 64 * 1024];
+    let mut written = 0;
+    loop {
+        let read = body.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        digest_verifier.update(&buf[..read]);
+        temp_file.write_all(&buf[..read]).await?;
+        written += read as u64;
+    }
+    temp_file.flush().await?;
+    if fsync {
+        temp_file.sync_all().await?;
+    }
+
+    Ok(written)
+}
+
+fn is_malformed_body(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::InvalidData | std::io::ErrorKind::UnexpectedEof
+    )
+}
+
+async fn rename_temp_file(
+    temp_file_path: &Path,
+    file_path: &Path,
+    fsync: bool,
+) -> std::io::Result<()> {
+    tokio::fs::rename(temp_file_path, file_path).await?;
+    if fsync {
+        sync_parent_directory(file_path).await?;
+    }
+
+    Ok(())
+}
+
+async fn link_temp_file(
+    temp_file_path: &Path,
+    file_path: &Path,
+    fsync: bool,
+) -> std::io::Result<()> {
+    tokio::fs::hard_link(temp_file_path, file_path).await?;
+    remove_temp_file(temp_file_path).await;
+    if fsync {
+        sync_parent_directory(file_path).await?;
+    }
+
+    Ok(())
+}
+
+async fn sync_parent_directory(file_path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = file_path.parent() {
+        tokio::fs::File::open(parent).await?.sync_all().await?;
+    }
+
+    Ok(())
+}
+
+async fn remove_temp_file(temp_file_path: &Path) {
+    if let Err(e) = tokio::fs::remove_file(temp_file_path).await {
+        println!("error: {e}");
+    }
+}
+
+pub fn handle_get_upload_form() -> HttpResponse {
+    let mut http_response = HttpResponse::new(HttpResponseCode::Ok, None, None);
+    http_response.set_body(ContentType::text_html(), UPLOAD_FORM);
+
+    http_response
+}
+
+pub async fn handle_post_upload(
+    directory: Option<&Path>,
+    body: &mut (impl AsyncRead + Unpin),
+    http_request: &HttpRequest,
+    authenticator: &Authenticator,
+    multipart_limits: &MultipartLimits,
+    fsync: bool,
+) -> HttpResponse {
+    let Some(directory) = directory else {
+        return handle_not_found();
+    };
+    let boundary = http_request
+        .content_type()
+        .filter(|content_type| {
+            content_type.media_type() == "multipart" && content_type.subtype() == "form-data"
+        })
+        .and_then(ContentType::boundary);
+    let Some(boundary) = boundary else {
+        return HttpResponse::new(HttpResponseCode::UnsupportedMediaType, None, None);
+    };
+    let max_body_size = match http_request.content_length() {
+        Some(content_length) if content_length as u64 > multipart_limits.max_body_size() => {
+            return HttpResponse::new(HttpResponseCode::ContentTooLarge, None, None);
+        }
+        Some(content_length) => content_length as u64,
+        None if http_request.is_chunked() => multipart_limits.max_body_size(),
+        None => return HttpResponse::new(HttpResponseCode::LengthRequired, None, None),
+    };
+
+    let mut multipart_reader = MultipartReader::new(body.take(max_body_size), boundary);
+    let multipart_form = read_multipart_form(
+        &mut multipart_reader,
+        directory,
+        http_request,
+        authenticator,
+        multipart_limits,
+        fsync,
+    )
+    .await;
+    let multipart_form = match multipart_form {
+        Ok(multipart_form) => multipart_form,
+        Err(HttpResponseCode::Unauthorized) => {
+            return handle_unauthorized(authenticator.challenges())
+        }
+        Err(http_response_code) => return HttpResponse::new(http_response_code, None, None),
+    };
+
+    let (content_type, body) = match ErrorPageFormat::from_accept(http_request.accept()) {
+        ErrorPageFormat::Html => {
+            let files = multipart_form
+                .files
+                .iter()
+                .map(|uploaded_file| {
+                    format!(
+                        "<li>{} ({} bytes)</li>",
+                        escape_html(&uploaded_file.filename),
+                        uploaded_file.size
+                    )
+                })
+                .collect::<String>();
+
+            (
+                ContentType::text_html(),
+                format!(
+                    "<!DOCTYPE html>\n<html>\n<head><title>Upload complete</title></head>\n<body>\n<h1>Upload complete</h1>\n<ul>{}</ul>\n<p><a href=\"/upload\">Upload more</a></p>\n</body>\n</html>\n",
+                    files
+                ),
+            )
+        }
+        ErrorPageFormat::Json => {
+            let files = multipart_form
+                .files
+                .iter()
+                .map(|uploaded_file| {
+                    json!({
+                        "name": uploaded_file.name,
+                        "filename": uploaded_file.filename,
+                        "content_type": uploaded_file
+                            .content_type
+                            .as_ref()
+                            .map(ContentType::to_string),
+                        "size": uploaded_file.size,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let fields = multipart_form
+                .fields
+                .iter()
+                .map(|(name, value)| json!({ "name": name, "value": value }))
+                .collect::<Vec<_>>();
+
+            (
+                ContentType::application_json(),
+                json!({ "files": files, "fields": fields }).to_string(),
+            )
+        }
+    };
+    let mut http_response = HttpResponse::new(HttpResponseCode::Created, None, None);
+    http_response.set_body(content_type, body);
+
+    http_response
+}
+
+struct MultipartForm {
+    fields: Vec<(String, String)>,
+    files: Vec<UploadedFile>,
+}
+
+struct UploadedFile {
+    name: String,
+    filename: String,
+    content_type: Option<ContentType>,
+    size: u64,
+}
+
+async fn read_multipart_form(
+    multipart_reader: &mut MultipartReader<impl AsyncRead + Unpin>,
+    directory: &Path,
+    http_request: &HttpRequest,
+    authenticator: &Authenticator,
+    multipart_limits: &MultipartLimits,
+    fsync: bool,
+) -> Result<MultipartForm, HttpResponseCode> {
+    let upload_prefix = http_request.claims().and_then(Claims::upload_prefix);
+    let mut multipart_form = MultipartForm {
+        fields: vec![],
+        files: vec![],
+    };
+    let mut parts = 0;
+    while let Some(part) = multipart_reader
+        .next_part()
+        .await
+        .map_err(multipart_error_code)?
+    {
+        parts += 1;
+        if parts > multipart_limits.max_parts() {
+            return Err(HttpResponseCode::ContentTooLarge);
+        }
+        let name = part.name().unwrap_or_default().to_string();
+        let content_type = part.content_type().cloned();
+
+        let Some(filename) = part.filename() else {
+            let mut value = vec![];
+            while let Some(chunk) = multipart_reader
+                .read_chunk()
+                .await
+                .map_err(multipart_error_code)?
+            {
+                if value.len() + chunk.len() > MAX_FIELD_SIZE {
+                    return Err(HttpResponseCode::ContentTooLarge);
+                }
+                value.extend_from_slice(&chunk);
+            }
+            let value = String::from_utf8(value).map_err(|_| HttpResponseCode::BadRequest)?;
+            multipart_form.fields.push((name, value));
+
+            continue;
+        };
+        let Some(filename) = sanitize_filename(filename) else {
+            continue;
+        };
+        if let Some(upload_prefix) = upload_prefix {
+            if !is_within_upload_prefix(&filename, upload_prefix) {
+                return Err(HttpResponseCode::Forbidden);
+            }
+        }
+        if !authenticator.authorizes(http_request, &format!("/files/{}", filename)) {
+            return Err(HttpResponseCode::Unauthorized);
+        }
+
+        let file_path = directory.join(&filename);
+        let temp_file_path = temp_file_path(&file_path);
+        let written = write_multipart_file(
+            multipart_reader,
+            &temp_file_path,
+            multipart_limits.max_part_size(),
+            fsync,
+        )
+        .await;
+        let written = match written {
+            Ok(written) => written,
+            Err(http_response_code) => {
+                remove_temp_file(&temp_file_path).await;
+
+                return Err(http_response_code);
+            }
+        };
+        match link_temp_file(&temp_file_path, &file_path, fsync).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                remove_temp_file(&temp_file_path).await;
+
+                return Err(HttpResponseCode::Conflict);
+            }
+            Err(e) => {
+                println!("error: {e}");
+                remove_temp_file(&temp_file_path).await;
+
+                return Err(HttpResponseCode::InternalServerError);
+            }
+        }
+        multipart_form.files.push(UploadedFile {
+            name,
+            filename,
+            content_type,
+            size: written,
+        });
+    }
+
+    Ok(multipart_form)
+}
+
+async fn write_multipart_file(
+    multipart_reader: &mut MultipartReader<impl AsyncRead + Unpin>,
+    temp_file_path: &Path,
+    max_part_size: u64,
+    fsync: bool,
+) -> Result<u64, HttpResponseCode> {
+    let mut temp_file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_file_path)
+        .await
+        .map_err(io_error_code)?;
+    let mut written = 0;
+    while let Some(chunk) = multipart_reader
+        .read_chunk()
+        .await
+        .map_err(multipart_error_code)?
+    {
+        written += chunk.len() as u64;
+        if written > max_part_size {
+            return Err(HttpResponseCode::ContentTooLarge);
+        }
+        temp_file.write_all(&chunk).await.map_err(io_error_code)?;
+    }
+    temp_file.flush().await.map_err(io_error_code)?;
+    if fsync {
+        temp_file.sync_all().await.map_err(io_error_code)?;
     }
+
+    Ok(written)
+}
+
+fn multipart_error_code(multipart_error: MultipartError) -> HttpResponseCode {
+    println!("error: {multipart_error}");
+
+    match multipart_error {
+        MultipartError::Io(_) => HttpResponseCode::InternalServerError,
+        MultipartError::Malformed(_) => HttpResponseCode::BadRequest,
+    }
+}
+
+fn io_error_code(io_error: std::io::Error) -> HttpResponseCode {
+    println!("error: {io_error}");
+
+    match io_error.kind() {
+        std::io::ErrorKind::NotFound => HttpResponseCode::NotFound,
+        _ => HttpResponseCode::InternalServerError,
+    }
+}
+
+fn handle_io_error(io_error: std::io::Error) -> HttpResponse {
+    HttpResponse::new(io_error_code(io_error), None, None)
 }
 
-pub async fn handle_post_files(file: String, directory: &PathBuf, text: String) -> HttpResponse {
+fn resolve_file_path(directory: &Path, file: &str) -> Option<PathBuf> {
     let mut file_path = PathBuf::from(directory);
-    file_path.push(file);
-    tokio::fs::write(file_path, text)
+    for segment in file.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return None,
+            _ if segment.contains('\\') => return None,
+            _ => file_path.push(segment),
+        }
+    }
+
+    Some(file_path)
+}
+
+fn is_within_upload_prefix(file: &str, upload_prefix: &str) -> bool {
+    has_path_prefix(
+        &format!("/{}", file),
+        &format!("/{}", upload_prefix.trim_start_matches('/')),
+    )
+}
+
+fn is_asset_path(file: &str) -> bool {
+    Path::new(file).extension().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn test_directory(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let directory = std::env::temp_dir().join(format!("{}-{:x}", name, nanos));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        directory
+    }
+
+    async fn parse_request(raw: &str) -> HttpRequest {
+        HttpRequest::from_reader(&mut raw.as_bytes(), None)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn put_file(
+        directory: &Path,
+        precondition: &str,
+        body: &str,
+        digest_cache: &DigestCache,
+        file_locks: &FileLocks,
+    ) -> u16 {
+        let http_request = parse_request(&format!(
+            "PUT /files/f.txt HTTP/1.1\r\nHost: x\r\n{}\r\nContent-Length: {}\r\n\r\n",
+            precondition,
+            body.len()
+        ))
+        .await;
+        let http_response = handle_put_files(
+            String::from("f.txt"),
+            Some(directory),
+            &mut body.as_bytes(),
+            &http_request,
+            UploadOptions {
+                entity_tag_mode: EntityTagMode::ContentHash,
+                digest_cache,
+                file_locks,
+                fsync: false,
+            },
+        )
+        .await;
+
+        http_response.http_response_code().code()
+    }
+
+    #[test]
+    fn upload_prefix_matches_whole_segments() {
+        assert!(is_within_upload_prefix("alice/a.txt", "alice"));
+        assert!(is_within_upload_prefix("alice/a.txt", "/alice/"));
+        assert!(is_within_upload_prefix("a.txt", "/"));
+        assert!(!is_within_upload_prefix("alice2/a.txt", "alice"));
+        assert!(!is_within_upload_prefix("alice-evil.txt", "alice"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn concurrent_create_only_uploads_commit_once() {
+        let directory = test_directory("create-only");
+        let digest_cache = DigestCache::new();
+        let file_locks = FileLocks::new();
+
+        let (first, second) = tokio::join!(
+            put_file(
+                &directory,
+                "If-None-Match: *",
+                "first",
+                &digest_cache,
+                &file_locks
+            ),
+            put_file(
+                &directory,
+                "If-None-Match: *",
+                "second",
+                &digest_cache,
+                &file_locks
+            ),
+        );
+        let contents = std::fs::read_to_string(directory.join("f.txt")).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let mut codes = [first, second];
+        codes.sort();
+        assert_eq!(codes, [201, 412]);
+        assert_eq!(contents, if first == 201 { "first" } else { "second" });
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn concurrent_if_match_uploads_commit_once() {
+        let directory = test_directory("if-match");
+        let digest_cache = DigestCache::new();
+        let file_locks = FileLocks::new();
+        let file_path = directory.join("f.txt");
+        std::fs::write(&file_path, "original").unwrap();
+        let metadata = std::fs::metadata(&file_path).unwrap();
+        let entity_tag = FileValidators::for_file(
+            &file_path,
+            &metadata,
+            EntityTagMode::ContentHash,
+            &digest_cache,
+        )
         .await
-        .expect("Can write new file");
+        .unwrap()
+        .entity_tag()
+        .to_string();
+        let if_match = format!("If-Match: {}", entity_tag);
+
+        let (first, second) = tokio::join!(
+            put_file(&directory, &if_match, "first", &digest_cache, &file_locks),
+            put_file(&directory, &if_match, "second", &digest_cache, &file_locks),
+        );
+        let contents = std::fs::read_to_string(&file_path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
 
-    HttpResponse::new(HttpResponseCode::Created, None, None)
+        let mut codes = [first, second];
+        codes.sort();
+        assert_eq!(codes, [204, 412]);
+        assert_eq!(contents, if first == 204 { "first" } else { "second" });
+    }
 }
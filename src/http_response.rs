@@ -1,78 +1,479 @@
-use std::fmt::Display;
+use std::{error::Error, fmt::Display, fs::File};
 
-use crate::http_header::HttpHeader;
+use bytes::Bytes;
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    http_header::{ContentLength, ContentType, HttpHeader},
+    http_request::HttpVersion,
+    http_stream::HttpStream,
+};
 
 pub enum HttpResponseCode {
+    Continue,
+    SwitchingProtocols,
+    Processing,
+    EarlyHints,
     Ok,
-    NotFound,
     Created,
+    Accepted,
+    NonAuthoritativeInformation,
+    NoContent,
+    ResetContent,
+    PartialContent,
+    MultiStatus,
+    AlreadyReported,
+    ImUsed,
+    MultipleChoices,
+    MovedPermanently,
+    Found,
+    SeeOther,
+    NotModified,
+    UseProxy,
+    TemporaryRedirect,
+    PermanentRedirect,
+    BadRequest,
+    Unauthorized,
+    PaymentRequired,
+    Forbidden,
+    NotFound,
+    MethodNotAllowed,
+    NotAcceptable,
+    ProxyAuthenticationRequired,
+    RequestTimeout,
+    Conflict,
+    Gone,
+    LengthRequired,
+    PreconditionFailed,
+    ContentTooLarge,
+    UriTooLong,
+    UnsupportedMediaType,
+    RangeNotSatisfiable,
+    ExpectationFailed,
+    ImATeapot,
+    MisdirectedRequest,
+    UnprocessableContent,
+    Locked,
+    FailedDependency,
+    TooEarly,
+    UpgradeRequired,
+    PreconditionRequired,
+    TooManyRequests,
+    RequestHeaderFieldsTooLarge,
+    UnavailableForLegalReasons,
+    InternalServerError,
+    NotImplemented,
+    BadGateway,
+    ServiceUnavailable,
+    GatewayTimeout,
+    HttpVersionNotSupported,
+    VariantAlsoNegotiates,
+    InsufficientStorage,
+    LoopDetected,
+    NotExtended,
+    NetworkAuthenticationRequired,
+    Other(u16),
 }
 
+impl HttpResponseCode {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Continue => 100,
+            Self::SwitchingProtocols => 101,
+            Self::Processing => 102,
+            Self::EarlyHints => 103,
+            Self::Ok => 200,
+            Self::Created => 201,
+            Self::Accepted => 202,
+            Self::NonAuthoritativeInformation => 203,
+            Self::NoContent => 204,
+            Self::ResetContent => 205,
+            Self::PartialContent => 206,
+            Self::MultiStatus => 207,
+            Self::AlreadyReported => 208,
+            Self::ImUsed => 226,
+            Self::MultipleChoices => 300,
+            Self::MovedPermanently => 301,
+            Self::Found => 302,
+            Self::SeeOther => 303,
+            Self::NotModified => 304,
+            Self::UseProxy => 305,
+            Self::TemporaryRedirect => 307,
+            Self::PermanentRedirect => 308,
+            Self::BadRequest => 400,
+            Self::Unauthorized => 401,
+            Self::PaymentRequired => 402,
+            Self::Forbidden => 403,
+            Self::NotFound => 404,
+            Self::MethodNotAllowed => 405,
+            Self::NotAcceptable => 406,
+            Self::ProxyAuthenticationRequired => 407,
+            Self::RequestTimeout => 408,
+            Self::Conflict => 409,
+            Self::Gone => 410,
+            Self::LengthRequired => 411,
+            Self::PreconditionFailed => 412,
+            Self::ContentTooLarge => 413,
+            Self::UriTooLong => 414,
+            Self::UnsupportedMediaType => 415,
+            Self::RangeNotSatisfiable => 416,
+            Self::ExpectationFailed => 417,
+            Self::ImATeapot => 418,
+            Self::MisdirectedRequest => 421,
+            Self::UnprocessableContent => 422,
+            Self::Locked => 423,
+            Self::FailedDependency => 424,
+            Self::TooEarly => 425,
+            Self::UpgradeRequired => 426,
+            Self::PreconditionRequired => 428,
+            Self::TooManyRequests => 429,
+            Self::RequestHeaderFieldsTooLarge => 431,
+            Self::UnavailableForLegalReasons => 451,
+            Self::InternalServerError => 500,
+            Self::NotImplemented => 501,
+            Self::BadGateway => 502,
+            Self::ServiceUnavailable => 503,
+            Self::GatewayTimeout => 504,
+            Self::HttpVersionNotSupported => 505,
+            Self::VariantAlsoNegotiates => 506,
+            Self::InsufficientStorage => 507,
+            Self::LoopDetected => 508,
+            Self::NotExtended => 510,
+            Self::NetworkAuthenticationRequired => 511,
+            Self::Other(code) => *code,
+        }
+    }
+
+    pub fn reason_phrase(&self) -> &'static str {
+        match self {
+            Self::Continue => "Continue",
+            Self::SwitchingProtocols => "Switching Protocols",
+            Self::Processing => "Processing",
+            Self::EarlyHints => "Early Hints",
+            Self::Ok => "OK",
+            Self::Created => "Created",
+            Self::Accepted => "Accepted",
+            Self::NonAuthoritativeInformation => "Non-Authoritative Information",
+            Self::NoContent => "No Content",
+            Self::ResetContent => "Reset Content",
+            Self::PartialContent => "Partial Content",
+            Self::MultiStatus => "Multi-Status",
+            Self::AlreadyReported => "Already Reported",
+            Self::ImUsed => "IM Used",
+            Self::MultipleChoices => "Multiple Choices",
+            Self::MovedPermanently => "Moved Permanently",
+            Self::Found => "Found",
+            Self::SeeOther => "See Other",
+            Self::NotModified => "Not Modified",
+            Self::UseProxy => "Use Proxy",
+            Self::TemporaryRedirect => "Temporary Redirect",
+            Self::PermanentRedirect => "Permanent Redirect",
+            Self::BadRequest => "Bad Request",
+            Self::Unauthorized => "Unauthorized",
+            Self::PaymentRequired => "Payment Required",
+            Self::Forbidden => "Forbidden",
+            Self::NotFound => "Not Found",
+            Self::MethodNotAllowed => "Method Not Allowed",
+            Self::NotAcceptable => "Not Acceptable",
+            Self::ProxyAuthenticationRequired => "Proxy Authentication Required",
+            Self::RequestTimeout => "Request Timeout",
+            Self::Conflict => "Conflict",
+            Self::Gone => "Gone",
+            Self::LengthRequired => "Length Required",
+            Self::PreconditionFailed => "Precondition Failed",
+            Self::ContentTooLarge => "Content Too Large",
+            Self::UriTooLong => "URI Too Long",
+            Self::UnsupportedMediaType => "Unsupported Media Type",
+            Self::RangeNotSatisfiable => "Range Not Satisfiable",
+            Self::ExpectationFailed => "Expectation Failed",
+            Self::ImATeapot => "I'm a teapot",
+            Self::MisdirectedRequest => "Misdirected Request",
+            Self::UnprocessableContent => "Unprocessable Content",
+            Self::Locked => "Locked",
+            Self::FailedDependency => "Failed Dependency",
+            Self::TooEarly => "Too Early",
+            Self::UpgradeRequired => "Upgrade Required",
+            Self::PreconditionRequired => "Precondition Required",
+            Self::TooManyRequests => "Too Many Requests",
+            Self::RequestHeaderFieldsTooLarge => "Request Header Fields Too Large",
+            Self::UnavailableForLegalReasons => "Unavailable For Legal Reasons",
+            Self::InternalServerError => "Internal Server Error",
+            Self::NotImplemented => "Not Implemented",
+            Self::BadGateway => "Bad Gateway",
+            Self::ServiceUnavailable => "Service Unavailable",
+            Self::GatewayTimeout => "Gateway Timeout",
+            Self::HttpVersionNotSupported => "HTTP Version Not Supported",
+            Self::VariantAlsoNegotiates => "Variant Also Negotiates",
+            Self::InsufficientStorage => "Insufficient Storage",
+            Self::LoopDetected => "Loop Detected",
+            Self::NotExtended => "Not Extended",
+            Self::NetworkAuthenticationRequired => "Network Authentication Required",
+            Self::Other(_) => "",
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.code() >= 400
+    }
+
+    pub fn allows_body(&self) -> bool {
+        self.code() >= 200 && self.code() != 204 && self.code() != 304
+    }
+}
+
+impl TryFrom<u16> for HttpResponseCode {
+    type Error = HttpResponseCodeError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        if !(100..=599).contains(&value) {
+            return Err(HttpResponseCodeError::new(value));
+        }
+
+        Ok(match value {
+            100 => Self::Continue,
+            101 => Self::SwitchingProtocols,
+            102 => Self::Processing,
+            103 => Self::EarlyHints,
+            200 => Self::Ok,
+            201 => Self::Created,
+            202 => Self::Accepted,
+            203 => Self::NonAuthoritativeInformation,
+            204 => Self::NoContent,
+            205 => Self::ResetContent,
+            206 => Self::PartialContent,
+            207 => Self::MultiStatus,
+            208 => Self::AlreadyReported,
+            226 => Self::ImUsed,
+            300 => Self::MultipleChoices,
+            301 => Self::MovedPermanently,
+            302 => Self::Found,
+            303 => Self::SeeOther,
+            304 => Self::NotModified,
+            305 => Self::UseProxy,
+            307 => Self::TemporaryRedirect,
+            308 => Self::PermanentRedirect,
+            400 => Self::BadRequest,
+            401 => Self::Unauthorized,
+            402 => Self::PaymentRequired,
+            403 => Self::Forbidden,
+            404 => Self::NotFound,
+            405 => Self::MethodNotAllowed,
+            406 => Self::NotAcceptable,
+            407 => Self::ProxyAuthenticationRequired,
+            408 => Self::RequestTimeout,
+            409 => Self::Conflict,
+            410 => Self::Gone,
+            411 => Self::LengthRequired,
+            412 => Self::PreconditionFailed,
+            413 => Self::ContentTooLarge,
+            414 => Self::UriTooLong,
+            415 => Self::UnsupportedMediaType,
+            416 => Self::RangeNotSatisfiable,
+            417 => Self::ExpectationFailed,
+            418 => Self::ImATeapot,
+            421 => Self::MisdirectedRequest,
+            422 => Self::UnprocessableContent,
+            423 => Self::Locked,
+            424 => Self::FailedDependency,
+            425 => Self::TooEarly,
+            426 => Self::UpgradeRequired,
+            428 => Self::PreconditionRequired,
+            429 => Self::TooManyRequests,
+            431 => Self::RequestHeaderFieldsTooLarge,
+            451 => Self::UnavailableForLegalReasons,
+            500 => Self::InternalServerError,
+            501 => Self::NotImplemented,
+            502 => Self::BadGateway,
+            503 => Self::ServiceUnavailable,
+            504 => Self::GatewayTimeout,
+            505 => Self::HttpVersionNotSupported,
+            506 => Self::VariantAlsoNegotiates,
+            507 => Self::InsufficientStorage,
+            508 => Self::LoopDetected,
+            510 => Self::NotExtended,
+            511 => Self::NetworkAuthenticationRequired,
+            _ => Self::Other(value),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpResponseCodeError {
+    code: u16,
+}
+
+impl HttpResponseCodeError {
+    fn new(code: u16) -> Self {
+        Self { code }
+    }
+}
+
+impl Display for HttpResponseCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid status code: {}", self.code)
+    }
+}
+
+impl Error for HttpResponseCodeError {}
+
 impl Display for HttpResponseCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Ok => write!(f, "200 OK"),
-            Self::NotFound => write!(f, "404 Not Found"),
-            Self::Created => write!(f, "201 Created"),
+        write!(f, "{} {}", self.code(), self.reason_phrase())
+    }
+}
+
+pub enum HttpResponseBody {
+    Bytes(Bytes),
+    File(FileBody),
+}
+
+pub enum FileBodyPart {
+    Bytes(Bytes),
+    Range { offset: u64, length: u64 },
+}
+
+pub struct FileBody {
+    file: File,
+    parts: Vec<FileBodyPart>,
+}
+
+impl FileBody {
+    pub fn new(file: File) -> Self {
+        Self {
+            file,
+            parts: vec![],
         }
     }
+
+    pub fn push_bytes(&mut self, bytes: impl Into<Bytes>) {
+        self.parts.push(FileBodyPart::Bytes(bytes.into()));
+    }
+
+    pub fn push_range(&mut self, offset: u64, length: u64) {
+        self.parts.push(FileBodyPart::Range { offset, length });
+    }
+
+    pub fn len(&self) -> u64 {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                FileBodyPart::Bytes(bytes) => bytes.len() as u64,
+                FileBodyPart::Range { length, .. } => *length,
+            })
+            .sum()
+    }
 }
 
 pub struct HttpResponse {
+    http_version: HttpVersion,
     http_response_code: HttpResponseCode,
     http_headers: Option<Vec<HttpHeader>>,
-    http_response_body: Option<String>,
+    http_response_body: Option<HttpResponseBody>,
 }
 
 impl HttpResponse {
     pub fn new(
         http_response_code: HttpResponseCode,
         http_headers: Option<Vec<HttpHeader>>,
-        http_response_body: Option<String>,
+        http_response_body: Option<Bytes>,
     ) -> Self {
         Self {
+            http_version: HttpVersion::Http11,
             http_response_code,
             http_headers,
-            http_response_body,
+            http_response_body: http_response_body.map(HttpResponseBody::Bytes),
         }
     }
 
-    fn status_line(&self) -> String {
-        format!("HTTP/1.1 {}", self.http_response_code)
+    pub fn from_file(
+        http_response_code: HttpResponseCode,
+        http_headers: Option<Vec<HttpHeader>>,
+        file_body: FileBody,
+    ) -> Self {
+        Self {
+            http_version: HttpVersion::Http11,
+            http_response_code,
+            http_headers,
+            http_response_body: Some(HttpResponseBody::File(file_body)),
+        }
     }
 
-    fn headers(&self) -> String {
-        match &self.http_headers {
-            Some(http_headers) => {
-                let mut headers = String::new();
-                for http_header in http_headers {
-                    headers.push_str(&http_header.to_string());
-                    headers.push_str("\r\n");
-                }
+    pub fn http_response_code(&self) -> &HttpResponseCode {
+        &self.http_response_code
+    }
 
-                headers
+    pub fn has_body(&self) -> bool {
+        self.http_response_body.is_some()
+    }
+
+    pub fn set_body(&mut self, content_type: ContentType, http_response_body: impl Into<Bytes>) {
+        let http_response_body = http_response_body.into();
+        self.add_header(HttpHeader::ContentType(content_type));
+        self.add_header(HttpHeader::ContentLength(ContentLength::new(
+            http_response_body.len(),
+        )));
+        self.http_response_body = Some(HttpResponseBody::Bytes(http_response_body));
+    }
+
+    pub fn set_http_version(&mut self, http_version: HttpVersion) {
+        self.http_version = http_version;
+    }
+
+    pub fn add_header(&mut self, http_header: HttpHeader) {
+        self.http_headers
+            .get_or_insert_with(Vec::new)
+            .push(http_header);
+    }
+
+    pub async fn write_to(&self, stream: &mut HttpStream) -> std::io::Result<()> {
+        let head = format!("{}\r\n{}\r\n", self.status_line(), self.headers());
+        match &self.http_response_body {
+            Some(HttpResponseBody::Bytes(http_response_body)) => {
+                let mut bytes = head.into_bytes();
+                bytes.extend_from_slice(http_response_body);
+                stream.write_all(&bytes).await?;
+            }
+            Some(HttpResponseBody::File(file_body)) => {
+                stream.write_all(head.as_bytes()).await?;
+                for part in &file_body.parts {
+                    match part {
+                        FileBodyPart::Bytes(bytes) => stream.write_all(bytes).await?,
+                        FileBodyPart::Range { offset, length } => {
+                            stream.send_file(&file_body.file, *offset, *length).await?
+                        }
+                    }
+                }
             }
-            None => String::from(""),
+            None => stream.write_all(head.as_bytes()).await?,
         }
+
+        stream.flush().await
     }
 
-    fn response_body(&self) -> String {
-        self.http_response_body
-            .as_ref()
-            .map_or(String::from(""), |http_response_body| {
-                http_response_body.clone()
-            })
+    fn status_line(&self) -> String {
+        format!("{} {}", self.http_version, self.http_response_code)
     }
-}
 
-impl Display for HttpResponse {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}\r\n{}\r\n{}",
-            self.status_line(),
-            self.headers(),
-            self.response_body()
-        )
+    fn headers(&self) -> String {
+        let mut headers = String::new();
+        let mut has_content_length = false;
+        for http_header in self.http_headers.iter().flatten() {
+            if let HttpHeader::ContentLength(_) = http_header {
+                has_content_length = true;
+            }
+            headers.push_str(&http_header.to_string());
+            headers.push_str("\r\n");
+        }
+        if !has_content_length
+            && self.http_response_body.is_none()
+            && self.http_response_code.allows_body()
+        {
+            headers.push_str(&format!(
+                "{}\r\n",
+                HttpHeader::ContentLength(ContentLength::new(0))
+            ));
+        }
+
+        headers
     }
 }
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+
+pub struct JwtValidator {
+    decoding_key: DecodingKey,
+    validation: Validation,
+}
+
+impl JwtValidator {
+    pub fn hs256(
+        secret: &Path,
+        audience: Option<&str>,
+        issuer: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let secret = std::fs::read(secret)?;
+        let secret = secret.trim_ascii_end();
+
+        Ok(Self::new(
+            DecodingKey::from_secret(secret),
+            Algorithm::HS256,
+            audience,
+            issuer,
+        ))
+    }
+
+    pub fn rs256(
+        public_key: &Path,
+        audience: Option<&str>,
+        issuer: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let public_key = std::fs::read(public_key)?;
+
+        Ok(Self::new(
+            DecodingKey::from_rsa_pem(&public_key)?,
+            Algorithm::RS256,
+            audience,
+            issuer,
+        ))
+    }
+
+    pub fn validate(&self, token: &str) -> Option<Claims> {
+        let token_data =
+            jsonwebtoken::decode::<Map<String, Value>>(token, &self.decoding_key, &self.validation)
+                .ok()?;
+
+        Some(Claims(token_data.claims))
+    }
+
+    fn new(
+        decoding_key: DecodingKey,
+        algorithm: Algorithm,
+        audience: Option<&str>,
+        issuer: Option<&str>,
+    ) -> Self {
+        let mut validation = Validation::new(algorithm);
+        validation.validate_nbf = true;
+        match audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        if let Some(issuer) = issuer {
+            validation.set_issuer(&[issuer]);
+        }
+
+        Self {
+            decoding_key,
+            validation,
+        }
+    }
+}
+
+pub struct Claims(Map<String, Value>);
+
+impl Claims {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    pub fn subject(&self) -> Option<&str> {
+        self.get("sub").and_then(Value::as_str)
+    }
+
+    pub fn upload_prefix(&self) -> Option<&str> {
+        self.get("upload_prefix")
+            .and_then(Value::as_str)
+            .or_else(|| self.subject())
+    }
+}
@@ -1,114 +1,705 @@
 mod args;
+mod auth;
+mod cookie;
+mod cors;
+mod digest;
+mod directory_listing;
+mod error_page;
+mod file_lock;
+mod file_validators;
+mod htpasswd;
 mod http_header;
+mod http_parser;
 mod http_request;
 mod http_request_handler;
 mod http_response;
+mod http_stream;
+mod jwt;
+mod mime;
+mod multipart;
+mod request_body;
+mod request_target;
+mod session;
+mod tls;
+mod virtual_host;
 
-use std::{path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use args::Args;
-use http_request::{HttpMethod, HttpRequest};
+use auth::Authenticator;
+use cookie::CookieOptions;
+use cors::CorsPolicy;
+use digest::DigestCache;
+use directory_listing::DirectoryListing;
+use error_page::ErrorPages;
+use file_lock::FileLocks;
+use file_validators::EntityTagMode;
+use htpasswd::HtpasswdFile;
+use http_header::{Host, HttpHeader, TokenList};
+use http_request::{HttpMethod, HttpRequest, HttpRequestError, HttpVersion};
+use http_request_handler::{FileOptions, UploadOptions};
+use http_response::HttpResponse;
+use http_stream::HttpStream;
 use itertools::Itertools;
-use tokio::io::AsyncWriteExt;
+use jwt::JwtValidator;
+use mime::MimeTypes;
+use multipart::MultipartLimits;
+use request_body::RequestBody;
+use session::{Session, SessionManager, SessionStore};
+use tls::ClientCertificatePolicy;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio_rustls::TlsAcceptor;
+use virtual_host::{Route, VirtualHost, VirtualHosts};
+
+const MAX_DRAINED_BODY_SIZE: u64 = 64 * 1024;
 
 enum HttpRequestType {
     GetRoot,
     GetEcho(String),
+    PostEcho,
     GetUserAgent,
+    GetSession,
+    PostSession,
+    PostSessionLogin,
+    PostSessionLogout,
     GetFile(String),
     PostFile(String),
+    PutFile(String),
+    GetUploadForm,
+    PostUpload,
+    Options,
 }
 
 impl HttpRequestType {
-    fn try_new(http_method: &HttpMethod, request_target: &str) -> Option<Self> {
-        let request_target_parts = request_target.split('/').collect_vec();
+    fn try_new(http_method: &HttpMethod, path_segments: &[String]) -> Option<Self> {
+        let request_target_parts = path_segments.iter().map(String::as_str).collect_vec();
         match http_method {
             HttpMethod::Get => match request_target_parts[..] {
                 ["", ""] => Some(HttpRequestType::GetRoot),
                 ["", "echo", echo] => Some(HttpRequestType::GetEcho(echo.to_string())),
                 ["", "user-agent"] => Some(HttpRequestType::GetUserAgent),
-                ["", "files", file] => Some(HttpRequestType::GetFile(file.to_string())),
+                ["", "session"] => Some(HttpRequestType::GetSession),
+                ["", "upload"] => Some(HttpRequestType::GetUploadForm),
+                ["", "files", ref file @ ..] => Some(HttpRequestType::GetFile(file.join("/"))),
                 _ => None,
             },
             HttpMethod::Post => match request_target_parts[..] {
+                ["", "echo"] => Some(HttpRequestType::PostEcho),
+                ["", "session"] => Some(HttpRequestType::PostSession),
+                ["", "session", "login"] => Some(HttpRequestType::PostSessionLogin),
+                ["", "session", "logout"] => Some(HttpRequestType::PostSessionLogout),
                 ["", "files", file] => Some(HttpRequestType::PostFile(file.to_string())),
+                ["", "upload"] => Some(HttpRequestType::PostUpload),
+                _ => None,
+            },
+            HttpMethod::Put => match request_target_parts[..] {
+                ["", "files", file] => Some(HttpRequestType::PutFile(file.to_string())),
                 _ => None,
             },
+            HttpMethod::Options => Some(HttpRequestType::Options),
+        }
+    }
+
+    fn route(&self) -> Option<Route> {
+        match self {
+            Self::GetRoot => Some(Route::Root),
+            Self::GetEcho(_) | Self::PostEcho => Some(Route::Echo),
+            Self::GetUserAgent => Some(Route::UserAgent),
+            Self::GetSession
+            | Self::PostSession
+            | Self::PostSessionLogin
+            | Self::PostSessionLogout => Some(Route::Session),
+            Self::GetFile(_) | Self::PostFile(_) | Self::PutFile(_) => Some(Route::Files),
+            Self::GetUploadForm | Self::PostUpload => Some(Route::Upload),
+            Self::Options => None,
         }
     }
 }
 
 struct TcpStreamHandler {
-    stream: tokio::net::TcpStream,
+    stream: BufReader<HttpStream>,
 }
 
 impl TcpStreamHandler {
-    fn new(stream: tokio::net::TcpStream) -> Self {
-        Self { stream }
+    fn new(stream: HttpStream) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
     }
 
     async fn handle(&mut self, config: Arc<tokio::sync::Mutex<Config>>) {
-        let http_request = HttpRequest::from_tcp_stream(&mut self.stream).await;
+        let mut pipelined_requests = 0;
+        loop {
+            let (keep_alive_timeout, max_pipelined_requests) = {
+                let config = config.lock().await;
+
+                (config.keep_alive_timeout(), config.max_pipelined_requests())
+            };
+            let http_request = tokio::time::timeout(
+                keep_alive_timeout,
+                HttpRequest::from_tcp_stream(&mut self.stream),
+            )
+            .await;
+            let http_request = match http_request {
+                Ok(Ok(Some(http_request))) => http_request,
+                Ok(Ok(None)) | Err(_) => return,
+                Ok(Err(HttpRequestError::Io(e))) => {
+                    println!("error: {e}");
+                    return;
+                }
+                Ok(Err(e)) => {
+                    println!("error: {e}");
+                    let mut http_response = HttpResponse::new(
+                        e.http_response_code(),
+                        Some(vec![HttpHeader::Connection(TokenList::from("close"))]),
+                        None,
+                    );
+                    let (content_type, body) = config
+                        .lock()
+                        .await
+                        .error_pages()
+                        .render(http_response.http_response_code(), None);
+                    http_response.set_body(content_type, body);
+                    if let Err(e) = http_response.write_to(self.stream.get_mut()).await {
+                        println!("error: {e}");
+                    }
+                    return;
+                }
+            };
+            let overloaded = pipelined_requests > max_pipelined_requests;
+            if !self.handle_request(&config, http_request, overloaded).await {
+                return;
+            }
+            if self.has_buffered_request().await {
+                pipelined_requests += 1;
+            } else {
+                pipelined_requests = 0;
+            }
+        }
+    }
+
+    async fn has_buffered_request(&mut self) -> bool {
+        let buffered = tokio::time::timeout(Duration::ZERO, self.stream.fill_buf()).await;
+
+        matches!(buffered, Ok(Ok(buffered)) if !buffered.is_empty())
+    }
+
+    async fn handle_request(
+        &mut self,
+        config: &tokio::sync::Mutex<Config>,
+        mut http_request: HttpRequest,
+        overloaded: bool,
+    ) -> bool {
+        let authenticator = config.lock().await.authenticator();
+        let (mut session, authenticated) = if overloaded {
+            (None, false)
+        } else {
+            let session = config.lock().await.load_session(&http_request).await;
+            let authenticated = authenticator
+                .authenticate(&mut http_request, session.as_ref())
+                .await;
+
+            (session, authenticated)
+        };
         let http_method = http_request.http_method();
-        let request_target = http_request.request_target();
-        let request_type = HttpRequestType::try_new(http_method, request_target);
+        let (virtual_host, upload_form) = {
+            let config = config.lock().await;
+
+            (config.virtual_host(&http_request), config.upload_form())
+        };
+        let request_type = HttpRequestType::try_new(http_method, http_request.path_segments())
+            .filter(|request_type| match (&virtual_host, request_type.route()) {
+                (_, Some(Route::Upload)) if !upload_form => false,
+                (Some(virtual_host), Some(route)) => virtual_host.allows(route),
+                _ => true,
+            });
+
+        let client_certificate_allowed = config
+            .lock()
+            .await
+            .client_certificate_policy()
+            .allows(&http_request);
+        let mut body = if http_request.is_chunked() {
+            RequestBody::chunked(&mut self.stream, http_request.expects_continue())
+        } else {
+            let content_length = http_request.content_length().unwrap_or_default();
 
-        let http_response = match request_type {
+            RequestBody::new(
+                &mut self.stream,
+                content_length as u64,
+                http_request.expects_continue(),
+            )
+        };
+
+        let mut http_response = match request_type {
+            _ if overloaded => http_request_handler::handle_service_unavailable(),
+            _ if !http_request.has_valid_host() => http_request_handler::handle_bad_request(),
+            _ if http_request.has_unsupported_transfer_encoding() => {
+                http_request_handler::handle_not_implemented()
+            }
+            _ if http_request.has_unsupported_expectation() => {
+                http_request_handler::handle_expectation_failed()
+            }
+            _ if !client_certificate_allowed => http_request_handler::handle_forbidden(),
+            _ if !authenticated => {
+                http_request_handler::handle_unauthorized(authenticator.challenges())
+            }
             Some(HttpRequestType::GetRoot) => http_request_handler::handle_get_root(),
-            Some(HttpRequestType::GetEcho(echo)) => http_request_handler::handle_get_echo(echo),
-            Some(HttpRequestType::GetUserAgent) => http_request_handler::handle_get_user_agent(
-                http_request
-                    .user_agent()
-                    .expect("Request should have an user agent"),
-            ),
+            Some(HttpRequestType::GetEcho(echo)) => {
+                http_request_handler::handle_get_echo(echo, &http_request)
+            }
+            Some(HttpRequestType::GetSession) => {
+                http_request_handler::handle_get_session(session.as_ref())
+            }
+            Some(HttpRequestType::PostSession) => {
+                let max_body_size = config.lock().await.max_body_size();
+
+                http_request_handler::handle_post_session(
+                    &mut body,
+                    &http_request,
+                    session.as_mut(),
+                    max_body_size,
+                )
+                .await
+            }
+            Some(HttpRequestType::PostSessionLogin) => {
+                http_request_handler::handle_post_session_login(
+                    &http_request,
+                    session.as_mut(),
+                    authenticator.challenges(),
+                )
+            }
+            Some(HttpRequestType::PostSessionLogout) => {
+                http_request_handler::handle_post_session_logout(session.as_mut())
+            }
+            Some(HttpRequestType::PostEcho) => {
+                let max_body_size = config.lock().await.max_body_size();
+
+                http_request_handler::handle_post_echo(&mut body, &http_request, max_body_size)
+                    .await
+            }
+            Some(HttpRequestType::GetUserAgent) => match http_request.user_agent() {
+                Some(user_agent) => {
+                    http_request_handler::handle_get_user_agent(user_agent, &http_request)
+                }
+                None => http_request_handler::handle_bad_request(),
+            },
             Some(HttpRequestType::GetFile(file)) => {
-                let config = config.lock().await;
-                let directory = config
-                    .directory()
-                    .expect("Should have a directory if handling file requests");
+                let (
+                    directory,
+                    entity_tag_mode,
+                    mime_types,
+                    directory_listing,
+                    spa_fallback,
+                    digest_cache,
+                ) = {
+                    let config = config.lock().await;
+                    let directory = config.directory(virtual_host.as_deref()).cloned();
 
-                http_request_handler::handle_get_files(file, directory).await
+                    (
+                        directory,
+                        config.entity_tag_mode(),
+                        config.mime_types(),
+                        config.directory_listing(virtual_host.as_deref()).copied(),
+                        config
+                            .spa_fallback(virtual_host.as_deref())
+                            .map(str::to_string),
+                        config.digest_cache(),
+                    )
+                };
+
+                http_request_handler::handle_get_files(
+                    file,
+                    directory.as_deref(),
+                    &http_request,
+                    FileOptions {
+                        entity_tag_mode,
+                        mime_types: &mime_types,
+                        directory_listing: directory_listing.as_ref(),
+                        spa_fallback: spa_fallback.as_deref(),
+                        digest_cache: &digest_cache,
+                    },
+                )
+                .await
             }
             Some(HttpRequestType::PostFile(file)) => {
+                let (directory, entity_tag_mode, digest_cache, file_locks, upload_fsync) = {
+                    let config = config.lock().await;
+                    let directory = config.directory(virtual_host.as_deref()).cloned();
+
+                    (
+                        directory,
+                        config.entity_tag_mode(),
+                        config.digest_cache(),
+                        config.file_locks(),
+                        config.upload_fsync(),
+                    )
+                };
+
+                http_request_handler::handle_post_files(
+                    file,
+                    directory.as_deref(),
+                    &mut body,
+                    &http_request,
+                    UploadOptions {
+                        entity_tag_mode,
+                        digest_cache: &digest_cache,
+                        file_locks: &file_locks,
+                        fsync: upload_fsync,
+                    },
+                )
+                .await
+            }
+            Some(HttpRequestType::PutFile(file)) => {
+                let (directory, entity_tag_mode, digest_cache, file_locks, upload_fsync) = {
+                    let config = config.lock().await;
+                    let directory = config.directory(virtual_host.as_deref()).cloned();
+
+                    (
+                        directory,
+                        config.entity_tag_mode(),
+                        config.digest_cache(),
+                        config.file_locks(),
+                        config.upload_fsync(),
+                    )
+                };
+
+                http_request_handler::handle_put_files(
+                    file,
+                    directory.as_deref(),
+                    &mut body,
+                    &http_request,
+                    UploadOptions {
+                        entity_tag_mode,
+                        digest_cache: &digest_cache,
+                        file_locks: &file_locks,
+                        fsync: upload_fsync,
+                    },
+                )
+                .await
+            }
+            Some(HttpRequestType::GetUploadForm) => http_request_handler::handle_get_upload_form(),
+            Some(HttpRequestType::PostUpload) => {
+                let (directory, multipart_limits, upload_fsync) = {
+                    let config = config.lock().await;
+                    let directory = config.directory(virtual_host.as_deref()).cloned();
+
+                    (directory, *config.multipart_limits(), config.upload_fsync())
+                };
+
+                http_request_handler::handle_post_upload(
+                    directory.as_deref(),
+                    &mut body,
+                    &http_request,
+                    &authenticator,
+                    &multipart_limits,
+                    upload_fsync,
+                )
+                .await
+            }
+            Some(HttpRequestType::Options) => {
                 let config = config.lock().await;
-                let directory = config
-                    .directory()
-                    .expect("Should have a directory if handling file requests");
-                let text = http_request
-                    .body()
-                    .expect("POST file has a request body")
-                    .to_string();
+                let preflight_headers = config
+                    .cors_policy()
+                    .filter(|_| http_request.is_cors_preflight())
+                    .map(|cors_policy| cors_policy.preflight_headers(&http_request));
 
-                http_request_handler::handle_post_files(file, directory, text).await
+                http_request_handler::handle_options(preflight_headers)
             }
             None => http_request_handler::handle_not_found(),
         };
-        let http_response = http_response.to_string();
-        self.stream
-            .write_all(http_response.as_bytes())
-            .await
-            .expect("Can send response");
+        let mut keep_alive = http_request.keep_alive()
+            && !http_request.has_unsupported_transfer_encoding()
+            && !http_request.has_ambiguous_length();
+        if body.awaiting_continue() {
+            keep_alive = keep_alive && body.is_complete();
+        } else if keep_alive && !body.is_complete() {
+            let drained = tokio::io::copy(
+                &mut (&mut body).take(MAX_DRAINED_BODY_SIZE),
+                &mut tokio::io::sink(),
+            )
+            .await;
+            keep_alive = drained.is_ok() && body.is_complete();
+        }
+        if overloaded {
+            keep_alive = keep_alive && self.has_buffered_request().await;
+        }
+        if http_response.http_response_code().is_error() && !http_response.has_body() {
+            let config = config.lock().await;
+            let (content_type, body) = config
+                .error_pages()
+                .render(http_response.http_response_code(), http_request.accept());
+            http_response.set_body(content_type, body);
+        }
+        if !http_request.is_cors_preflight() {
+            if let Some(cors_policy) = config.lock().await.cors_policy() {
+                cors_policy.apply(&http_request, &mut http_response);
+            }
+        }
+        if let Some(session) = session {
+            let config = config.lock().await;
+            if let Some(session_manager) = config.session_manager() {
+                match session_manager.save(session, config.cookie_options()).await {
+                    Ok(Some(set_cookie)) => {
+                        http_response.add_header(HttpHeader::SetCookie(set_cookie))
+                    }
+                    Ok(None) => {}
+                    Err(e) => println!("error: {e}"),
+                }
+            }
+        }
+        if !keep_alive {
+            http_response.add_header(HttpHeader::Connection(TokenList::from("close")));
+        } else if http_request.http_version() == HttpVersion::Http10 {
+            http_response.add_header(HttpHeader::Connection(TokenList::from("keep-alive")));
+        }
+        http_response.set_http_version(http_request.http_version());
+        if let Err(e) = http_response.write_to(self.stream.get_mut()).await {
+            println!("error: {e}");
+
+            return false;
+        }
+
+        keep_alive
     }
 }
 
 struct Config {
     directory: Option<PathBuf>,
+    client_certificate_policy: ClientCertificatePolicy,
+    authenticator: Arc<Authenticator>,
+    cors_policy: Option<CorsPolicy>,
+    error_pages: ErrorPages,
+    entity_tag_mode: EntityTagMode,
+    mime_types: Arc<MimeTypes>,
+    directory_listing: Option<DirectoryListing>,
+    spa_fallback: Option<String>,
+    upload_fsync: bool,
+    upload_form: bool,
+    digest_cache: Arc<DigestCache>,
+    file_locks: Arc<FileLocks>,
+    multipart_limits: MultipartLimits,
+    max_body_size: usize,
+    cookie_options: CookieOptions,
+    session_manager: Option<SessionManager>,
+    virtual_hosts: VirtualHosts,
+    keep_alive_timeout: Duration,
+    max_pipelined_requests: usize,
 }
 
 impl Config {
-    fn new(directory: Option<PathBuf>) -> Self {
-        Self { directory }
+    fn directory<'a>(&'a self, virtual_host: Option<&'a VirtualHost>) -> Option<&'a PathBuf> {
+        match virtual_host {
+            Some(virtual_host) => Some(virtual_host.directory()),
+            None => self.directory.as_ref(),
+        }
+    }
+
+    fn cors_policy(&self) -> Option<&CorsPolicy> {
+        self.cors_policy.as_ref()
+    }
+
+    fn error_pages(&self) -> &ErrorPages {
+        &self.error_pages
+    }
+
+    fn entity_tag_mode(&self) -> EntityTagMode {
+        self.entity_tag_mode
+    }
+
+    fn mime_types(&self) -> Arc<MimeTypes> {
+        Arc::clone(&self.mime_types)
+    }
+
+    fn directory_listing<'a>(
+        &'a self,
+        virtual_host: Option<&'a VirtualHost>,
+    ) -> Option<&'a DirectoryListing> {
+        match virtual_host {
+            Some(virtual_host) => virtual_host.directory_listing(),
+            None => self.directory_listing.as_ref(),
+        }
+    }
+
+    fn spa_fallback<'a>(&'a self, virtual_host: Option<&'a VirtualHost>) -> Option<&'a str> {
+        match virtual_host {
+            Some(virtual_host) => virtual_host.spa_fallback(),
+            None => self.spa_fallback.as_deref(),
+        }
+    }
+
+    fn keep_alive_timeout(&self) -> Duration {
+        self.keep_alive_timeout
+    }
+
+    fn max_pipelined_requests(&self) -> usize {
+        self.max_pipelined_requests
+    }
+
+    fn virtual_host(&self, http_request: &HttpRequest) -> Option<Arc<VirtualHost>> {
+        let hostname = http_request.host().map(Host::hostname);
+
+        self.virtual_hosts.resolve(hostname.as_deref())
+    }
+
+    fn upload_fsync(&self) -> bool {
+        self.upload_fsync
+    }
+
+    fn upload_form(&self) -> bool {
+        self.upload_form
+    }
+
+    fn digest_cache(&self) -> Arc<DigestCache> {
+        Arc::clone(&self.digest_cache)
+    }
+
+    fn file_locks(&self) -> Arc<FileLocks> {
+        Arc::clone(&self.file_locks)
+    }
+
+    fn multipart_limits(&self) -> &MultipartLimits {
+        &self.multipart_limits
+    }
+
+    fn max_body_size(&self) -> usize {
+        self.max_body_size
+    }
+
+    fn cookie_options(&self) -> &CookieOptions {
+        &self.cookie_options
+    }
+
+    fn session_manager(&self) -> Option<&SessionManager> {
+        self.session_manager.as_ref()
+    }
+
+    async fn load_session(&self, http_request: &HttpRequest) -> Option<Session> {
+        match &self.session_manager {
+            Some(session_manager) => Some(session_manager.load(http_request).await),
+            None => None,
+        }
     }
 
-    fn directory(&self) -> Option<&PathBuf> {
-        self.directory.as_ref()
+    fn authenticator(&self) -> Arc<Authenticator> {
+        Arc::clone(&self.authenticator)
+    }
+
+    fn client_certificate_policy(&self) -> &ClientCertificatePolicy {
+        &self.client_certificate_policy
     }
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let mut args = Args::from_env();
-    let config = Config::new(args.take_directory());
+    let tls_acceptor = match (args.take_tls_cert(), args.take_tls_key()) {
+        (Some(tls_cert), Some(tls_key)) => Some(tls::acceptor(
+            &tls_cert,
+            &tls_key,
+            args.take_tls_client_ca().as_deref(),
+            args.tls_client_auth(),
+        )?),
+        (Some(_), None) => anyhow::bail!("--tls-cert requires --tls-key"),
+        (None, Some(_)) => anyhow::bail!("--tls-key requires --tls-cert"),
+        (None, None) if args.take_tls_client_ca().is_some() => {
+            anyhow::bail!("--tls-client-ca requires --tls-cert and --tls-key")
+        }
+        (None, None) => None,
+    };
+    let jwt_audience = args.take_jwt_audience();
+    let jwt_issuer = args.take_jwt_issuer();
+    let jwt_validator = match (args.take_jwt_hs256_key(), args.take_jwt_rs256_key()) {
+        (Some(secret), _) => Some(JwtValidator::hs256(
+            &secret,
+            jwt_audience.as_deref(),
+            jwt_issuer.as_deref(),
+        )?),
+        (None, Some(public_key)) => Some(JwtValidator::rs256(
+            &public_key,
+            jwt_audience.as_deref(),
+            jwt_issuer.as_deref(),
+        )?),
+        (None, None) => None,
+    };
+    let authenticator = Authenticator::new(
+        args.take_auth_realm()
+            .unwrap_or_else(|| String::from("http-server")),
+        args.take_auth_rules(),
+        args.take_htpasswd().map(HtpasswdFile::new),
+        args.take_bearer_tokens(),
+        jwt_validator,
+    );
+    let virtual_hosts = VirtualHosts::new(
+        args.take_virtual_hosts(),
+        args.take_default_host().as_deref(),
+    )?;
+    let session_keys = args.take_session_keys();
+    let session_manager = (!session_keys.is_empty()).then(|| {
+        let session_store = match args.take_session_dir() {
+            Some(session_dir) => SessionStore::file(session_dir),
+            None => SessionStore::memory(),
+        };
+
+        SessionManager::new(
+            args.take_session_cookie()
+                .unwrap_or_else(|| String::from("session")),
+            session_keys.into_iter().map(String::into_bytes).collect(),
+            session_store,
+            Duration::from_secs(args.session_ttl()),
+        )
+    });
+    let cors_allowed_origins = args.take_cors_allowed_origins();
+    let cors_policy = (!cors_allowed_origins.is_empty()).then(|| {
+        CorsPolicy::new(
+            cors_allowed_origins,
+            args.take_cors_allowed_methods(),
+            args.take_cors_allowed_headers(),
+            args.cors_allow_credentials(),
+            args.cors_max_age(),
+            args.take_cors_exposed_headers(),
+        )
+    });
+    let mut error_pages = ErrorPages::new();
+    for (code, path) in args.take_error_pages() {
+        error_pages.add_template(code, &path)?;
+    }
+    let mime_types = MimeTypes::new(args.take_mime_types(), args.mime_sniff());
+    let config = Config {
+        directory: args.take_directory(),
+        client_certificate_policy: ClientCertificatePolicy::new(
+            args.take_tls_client_cert_paths(),
+            args.take_tls_client_allowed_names(),
+        ),
+        authenticator: Arc::new(authenticator),
+        cors_policy,
+        error_pages,
+        entity_tag_mode: args.entity_tag_mode(),
+        mime_types: Arc::new(mime_types),
+        directory_listing: args
+            .directory_listing()
+            .then(|| DirectoryListing::new(args.directory_listing_hidden())),
+        spa_fallback: args.take_spa_fallback(),
+        upload_fsync: args.upload_fsync(),
+        upload_form: args.upload_form(),
+        digest_cache: Arc::new(DigestCache::new()),
+        file_locks: Arc::new(FileLocks::new()),
+        multipart_limits: MultipartLimits::new(
+            args.multipart_max_parts(),
+            args.multipart_max_part_size(),
+        ),
+        max_body_size: args.max_body_size(),
+        cookie_options: CookieOptions::new(
+            args.take_cookie_domain(),
+            args.cookie_secure(),
+            args.cookie_same_site(),
+            args.cookie_partitioned(),
+        ),
+        session_manager,
+        virtual_hosts,
+        keep_alive_timeout: Duration::from_secs(args.keep_alive_timeout()),
+        max_pipelined_requests: args.max_pipelined_requests(),
+    };
     let config = Arc::new(tokio::sync::Mutex::new(config));
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:4221")
@@ -120,8 +711,9 @@ async fn main() -> anyhow::Result<()> {
         match connection {
             Ok((stream, _)) => {
                 let config = config.clone();
+                let tls_acceptor = tls_acceptor.clone();
                 tokio::spawn(async move {
-                    handle_tcp_stream(stream, config).await;
+                    handle_tcp_stream(stream, tls_acceptor, config).await;
                 });
             }
             Err(e) => {
@@ -131,7 +723,21 @@ async fn main() -> anyhow::Result<()> {
     }
 }
 
-async fn handle_tcp_stream(stream: tokio::net::TcpStream, config: Arc<tokio::sync::Mutex<Config>>) {
+async fn handle_tcp_stream(
+    stream: tokio::net::TcpStream,
+    tls_acceptor: Option<TlsAcceptor>,
+    config: Arc<tokio::sync::Mutex<Config>>,
+) {
+    let stream = match tls_acceptor {
+        Some(tls_acceptor) => match tls_acceptor.accept(stream).await {
+            Ok(tls_stream) => HttpStream::Tls(Box::new(tls_stream)),
+            Err(e) => {
+                println!("error: {e}");
+                return;
+            }
+        },
+        None => HttpStream::Plain(stream),
+    };
     let mut tcp_stream_handler = TcpStreamHandler::new(stream);
     tcp_stream_handler.handle(config).await;
 }
@@ -0,0 +1,167 @@
+use std::{collections::HashMap, error::Error, fmt::Display, path::Path};
+
+use crate::http_header::ContentType;
+
+const BUILT_IN_MIME_TYPES: [(&str, &str); 45] = [
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("css", "text/css"),
+    ("js", "text/javascript"),
+    ("mjs", "text/javascript"),
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("csv", "text/csv"),
+    ("xml", "application/xml"),
+    ("json", "application/json"),
+    ("map", "application/json"),
+    ("webmanifest", "application/manifest+json"),
+    ("wasm", "application/wasm"),
+    ("pdf", "application/pdf"),
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+    ("tar", "application/x-tar"),
+    ("7z", "application/x-7z-compressed"),
+    ("jar", "application/java-archive"),
+    ("deb", "application/vnd.debian.binary-package"),
+    ("rpm", "application/x-rpm"),
+    ("iso", "application/x-iso9660-image"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("toml", "application/toml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("svg", "image/svg+xml"),
+    ("ico", "image/vnd.microsoft.icon"),
+    ("bmp", "image/bmp"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("mp3", "audio/mpeg"),
+    ("ogg", "audio/ogg"),
+    ("wav", "audio/wav"),
+    ("mp4", "video/mp4"),
+    ("webm", "video/webm"),
+    ("mov", "video/quicktime"),
+    ("mkv", "video/x-matroska"),
+];
+
+const MAGIC_NUMBERS: [(&[u8], &str); 9] = [
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\0asm", "application/wasm"),
+    (b"\x1aE\xdf\xa3", "video/webm"),
+];
+
+pub struct MimeTypes {
+    by_extension: HashMap<String, ContentType>,
+    sniff: bool,
+}
+
+impl MimeTypes {
+    pub fn new(extra_mime_types: Vec<(String, ContentType)>, sniff: bool) -> Self {
+        let mut by_extension = BUILT_IN_MIME_TYPES
+            .iter()
+            .map(|(extension, mime_type)| {
+                let content_type =
+                    ContentType::try_from(*mime_type).expect("Built-in MIME types are valid");
+
+                (extension.to_string(), content_type)
+            })
+            .collect::<HashMap<_, _>>();
+        for (extension, content_type) in extra_mime_types {
+            by_extension.insert(extension.to_ascii_lowercase(), content_type);
+        }
+
+        Self {
+            by_extension,
+            sniff,
+        }
+    }
+
+    pub fn content_type(&self, path: &Path, file_contents: &[u8]) -> ContentType {
+        let by_extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| self.by_extension.get(&extension.to_ascii_lowercase()));
+
+        match by_extension {
+            Some(content_type) => content_type.clone(),
+            None if self.sniff => sniff(file_contents),
+            None => ContentType::application_octet_stream(),
+        }
+    }
+}
+
+fn sniff(file_contents: &[u8]) -> ContentType {
+    let magic_number = MAGIC_NUMBERS
+        .iter()
+        .find(|(magic_number, _)| file_contents.starts_with(magic_number));
+    if let Some((_, mime_type)) = magic_number {
+        return ContentType::try_from(*mime_type).expect("Built-in MIME types are valid");
+    }
+    if file_contents.len() >= 12 && &file_contents[0..4] == b"RIFF" {
+        match &file_contents[8..12] {
+            b"WEBP" => return ContentType::new("image", "webp"),
+            b"WAVE" => return ContentType::new("audio", "wav"),
+            _ => {}
+        }
+    }
+    if file_contents.len() >= 8 && &file_contents[4..8] == b"ftyp" {
+        return ContentType::new("video", "mp4");
+    }
+
+    let head = &file_contents[..file_contents.len().min(1024)];
+    let is_utf8 = match std::str::from_utf8(head) {
+        Ok(_) => true,
+        Err(utf8_error) => utf8_error.error_len().is_none(),
+    };
+    let is_text = is_utf8
+        && !head
+            .iter()
+            .any(|byte| byte.is_ascii_control() && !b"\t\n\r\x0c".contains(byte));
+    if is_text {
+        ContentType::text_plain().with_parameter("charset", "utf-8")
+    } else {
+        ContentType::application_octet_stream()
+    }
+}
+
+pub fn parse_mime_type_mapping(value: &str) -> Result<(String, ContentType), MimeTypeError> {
+    let (extension, mime_type) = value
+        .split_once('=')
+        .ok_or_else(|| MimeTypeError::new(value))?;
+    let content_type = ContentType::try_from(mime_type).map_err(|_| MimeTypeError::new(value))?;
+
+    Ok((extension.trim_start_matches('.').to_string(), content_type))
+}
+
+#[derive(Debug)]
+pub struct MimeTypeError {
+    mime_type: String,
+}
+
+impl MimeTypeError {
+    fn new(mime_type: &str) -> Self {
+        Self {
+            mime_type: mime_type.to_string(),
+        }
+    }
+}
+
+impl Display for MimeTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid MIME type mapping: {}", self.mime_type)
+    }
+}
+
+impl Error for MimeTypeError {}
@@ -0,0 +1,241 @@
+use std::{error::Error, fmt::Display};
+
+use bytes::{Buf, Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::http_header::{ContentDisposition, ContentType, HttpHeader};
+
+const MAX_PART_HEADERS_SIZE: usize = 8 * 1024;
+const READ_SIZE: usize = 64 * 1024;
+const MAX_DELIMITER_SIZE: usize = 80;
+
+#[derive(Clone, Copy)]
+pub struct MultipartLimits {
+    max_parts: usize,
+    max_part_size: u64,
+}
+
+impl MultipartLimits {
+    pub fn new(max_parts: usize, max_part_size: u64) -> Self {
+        Self {
+            max_parts,
+            max_part_size,
+        }
+    }
+
+    pub fn max_parts(&self) -> usize {
+        self.max_parts
+    }
+
+    pub fn max_part_size(&self) -> u64 {
+        self.max_part_size
+    }
+
+    pub fn max_body_size(&self) -> u64 {
+        let max_part_overhead = (MAX_PART_HEADERS_SIZE + MAX_DELIMITER_SIZE) as u64;
+
+        (self.max_parts as u64).saturating_mul(self.max_part_size.saturating_add(max_part_overhead))
+    }
+}
+
+pub struct MultipartPart {
+    content_disposition: Option<ContentDisposition>,
+    content_type: Option<ContentType>,
+}
+
+impl MultipartPart {
+    pub fn name(&self) -> Option<&str> {
+        self.content_disposition.as_ref()?.name()
+    }
+
+    pub fn filename(&self) -> Option<&str> {
+        self.content_disposition.as_ref()?.filename()
+    }
+
+    pub fn content_type(&self) -> Option<&ContentType> {
+        self.content_type.as_ref()
+    }
+}
+
+#[derive(PartialEq)]
+enum MultipartState {
+    Preamble,
+    Body,
+    AfterDelimiter,
+    Done,
+}
+
+pub struct MultipartReader<R> {
+    body: R,
+    delimiter: Vec<u8>,
+    buf: BytesMut,
+    state: MultipartState,
+}
+
+impl<R: AsyncRead + Unpin> MultipartReader<R> {
+    pub fn new(body: R, boundary: &str) -> Self {
+        let mut delimiter = b"\r\n--".to_vec();
+        delimiter.extend_from_slice(boundary.as_bytes());
+
+        Self {
+            body,
+            delimiter,
+            buf: BytesMut::from(&b"\r\n"[..]),
+            state: MultipartState::Preamble,
+        }
+    }
+
+    pub async fn next_part(&mut self) -> Result<Option<MultipartPart>, MultipartError> {
+        match self.state {
+            MultipartState::Preamble => loop {
+                if let Some(position) = self.find_delimiter() {
+                    self.buf.advance(position + self.delimiter.len());
+                    break;
+                }
+                let keep = self.delimiter.len() - 1;
+                if self.buf.len() > keep {
+                    self.buf.advance(self.buf.len() - keep);
+                }
+                self.fill().await?;
+            },
+            MultipartState::Body => while self.read_chunk().await?.is_some() {},
+            MultipartState::AfterDelimiter => {}
+            MultipartState::Done => return Ok(None),
+        }
+
+        while self.buf.len() < 2 {
+            self.fill().await?;
+        }
+        if self.buf.starts_with(b"--") {
+            self.state = MultipartState::Done;
+
+            return Ok(None);
+        }
+        if !self.buf.starts_with(b"\r\n") {
+            return Err(MultipartError::Malformed(String::from(
+                "delimiter is not followed by CRLF",
+            )));
+        }
+        self.buf.advance(2);
+
+        let headers_end = loop {
+            if self.buf.starts_with(b"\r\n") {
+                break 0;
+            }
+            if let Some(position) = find(&self.buf, b"\r\n\r\n") {
+                break position;
+            }
+            if self.buf.len() > MAX_PART_HEADERS_SIZE {
+                return Err(MultipartError::Malformed(String::from(
+                    "part headers are too large",
+                )));
+            }
+            self.fill().await?;
+        };
+        let headers = self.buf.split_to(headers_end);
+        self.buf.advance(if headers_end == 0 { 2 } else { 4 });
+        let headers = std::str::from_utf8(&headers)
+            .map_err(|_| MultipartError::Malformed(String::from("part headers are not UTF-8")))?;
+
+        let mut part = MultipartPart {
+            content_disposition: None,
+            content_type: None,
+        };
+        for header in headers.split("\r\n").filter(|header| !header.is_empty()) {
+            match HttpHeader::try_from(header) {
+                Ok(HttpHeader::ContentDisposition(content_disposition)) => {
+                    part.content_disposition = Some(content_disposition);
+                }
+                Ok(HttpHeader::ContentType(content_type)) => {
+                    part.content_type = Some(content_type);
+                }
+                Ok(_) => {}
+                Err(e) => return Err(MultipartError::Malformed(e.to_string())),
+            }
+        }
+        self.state = MultipartState::Body;
+
+        Ok(Some(part))
+    }
+
+    pub async fn read_chunk(&mut self) -> Result<Option<Bytes>, MultipartError> {
+        if self.state != MultipartState::Body {
+            return Ok(None);
+        }
+
+        loop {
+            if let Some(position) = self.find_delimiter() {
+                if position > 0 {
+                    return Ok(Some(self.buf.split_to(position).freeze()));
+                }
+                self.buf.advance(self.delimiter.len());
+                self.state = MultipartState::AfterDelimiter;
+
+                return Ok(None);
+            }
+            let available = self.buf.len().saturating_sub(self.delimiter.len() - 1);
+            if available > 0 {
+                return Ok(Some(self.buf.split_to(available).freeze()));
+            }
+            self.fill().await?;
+        }
+    }
+
+    fn find_delimiter(&self) -> Option<usize> {
+        find(&self.buf, &self.delimiter)
+    }
+
+    async fn fill(&mut self) -> Result<(), MultipartError> {
+        self.buf.reserve(READ_SIZE);
+        let read = self.body.read_buf(&mut self.buf).await?;
+        if read == 0 {
+            return Err(MultipartError::Malformed(String::from(
+                "body ended before the closing delimiter",
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+pub fn sanitize_filename(filename: &str) -> Option<String> {
+    let filename = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_control() { '_' } else { c })
+        .collect::<String>();
+    let filename = filename.trim().trim_start_matches('.');
+
+    (!filename.is_empty()).then(|| filename.to_string())
+}
+
+#[derive(Debug)]
+pub enum MultipartError {
+    Io(std::io::Error),
+    Malformed(String),
+}
+
+impl From<std::io::Error> for MultipartError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl Display for MultipartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(io_err) => write!(f, "{}", io_err),
+            Self::Malformed(reason) => write!(f, "Invalid multipart body: {}", reason),
+        }
+    }
+}
+
+impl Error for MultipartError {}
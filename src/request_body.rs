@@ -0,0 +1,163 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use bytes::BytesMut;
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt, AsyncWrite, BufReader, ReadBuf, Take};
+
+use crate::{
+    http_parser::{self, Parsed},
+    http_stream::HttpStream,
+};
+
+const CONTINUE: &[u8] = b"HTTP/1.1 100 Continue\r\n\r\n";
+const MAX_CHUNK_METADATA_SIZE: usize = 8 * 1024;
+
+#[derive(Clone, Copy)]
+enum ChunkState {
+    Size,
+    Data,
+    DataEnd,
+    Trailers,
+    Done,
+}
+
+pub struct RequestBody<'a> {
+    body: Take<&'a mut BufReader<HttpStream>>,
+    chunk_state: Option<ChunkState>,
+    chunk_metadata: BytesMut,
+    interim_response: Option<&'static [u8]>,
+}
+
+impl<'a> RequestBody<'a> {
+    pub fn new(
+        stream: &'a mut BufReader<HttpStream>,
+        content_length: u64,
+        expect_continue: bool,
+    ) -> Self {
+        Self {
+            body: stream.take(content_length),
+            chunk_state: None,
+            chunk_metadata: BytesMut::new(),
+            interim_response: (expect_continue && content_length > 0).then_some(CONTINUE),
+        }
+    }
+
+    pub fn chunked(stream: &'a mut BufReader<HttpStream>, expect_continue: bool) -> Self {
+        Self {
+            body: stream.take(0),
+            chunk_state: Some(ChunkState::Size),
+            chunk_metadata: BytesMut::new(),
+            interim_response: expect_continue.then_some(CONTINUE),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        match self.chunk_state {
+            Some(chunk_state) => matches!(chunk_state, ChunkState::Done),
+            None => self.body.limit() == 0,
+        }
+    }
+
+    pub fn awaiting_continue(&self) -> bool {
+        self.interim_response.is_some()
+    }
+
+    fn poll_chunk_metadata<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        parser: fn(&[u8]) -> Parsed<T>,
+    ) -> Poll<io::Result<T>> {
+        loop {
+            let stream = Pin::new(&mut **self.body.get_mut());
+            let available = ready!(stream.poll_fill_buf(cx))?;
+            if available.is_empty() {
+                return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+            }
+            let buffered = self.chunk_metadata.len();
+            self.chunk_metadata.extend_from_slice(available);
+
+            let parsed = parser(&self.chunk_metadata)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let stream = Pin::new(&mut **self.body.get_mut());
+            if let Some((parsed, consumed)) = parsed {
+                stream.consume(consumed - buffered);
+                self.chunk_metadata.clear();
+
+                return Poll::Ready(Ok(parsed));
+            }
+            stream.consume(self.chunk_metadata.len() - buffered);
+            if self.chunk_metadata.len() > MAX_CHUNK_METADATA_SIZE {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "chunk metadata is too large",
+                )));
+            }
+        }
+    }
+}
+
+impl AsyncRead for RequestBody<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        while let Some(interim_response) = this.interim_response {
+            let stream = this.body.get_mut().get_mut();
+            if interim_response.is_empty() {
+                ready!(Pin::new(stream).poll_flush(cx))?;
+                this.interim_response = None;
+                break;
+            }
+            let written = ready!(Pin::new(stream).poll_write(cx, interim_response))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            this.interim_response = Some(&interim_response[written..]);
+        }
+
+        loop {
+            let chunk_state = match this.chunk_state {
+                Some(ChunkState::Size) => {
+                    let chunk_size =
+                        ready!(this.poll_chunk_metadata(cx, http_parser::parse_chunk_size))?;
+                    this.body.set_limit(chunk_size);
+                    if chunk_size == 0 {
+                        ChunkState::Trailers
+                    } else {
+                        ChunkState::Data
+                    }
+                }
+                Some(ChunkState::Data) => {
+                    let filled = buf.filled().len();
+                    ready!(Pin::new(&mut this.body).poll_read(cx, buf))?;
+                    if buf.filled().len() == filled && buf.remaining() > 0 {
+                        return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                    }
+                    if this.body.limit() == 0 {
+                        this.chunk_state = Some(ChunkState::DataEnd);
+                    }
+
+                    return Poll::Ready(Ok(()));
+                }
+                Some(ChunkState::DataEnd) => {
+                    ready!(this.poll_chunk_metadata(cx, http_parser::parse_chunk_data_end))?;
+
+                    ChunkState::Size
+                }
+                Some(ChunkState::Trailers) => {
+                    ready!(this.poll_chunk_metadata(cx, http_parser::parse_trailer_section))?;
+
+                    ChunkState::Done
+                }
+                Some(ChunkState::Done) => return Poll::Ready(Ok(())),
+                None => return Pin::new(&mut this.body).poll_read(cx, buf),
+            };
+            this.chunk_state = Some(chunk_state);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use itertools::Itertools;
+
+pub struct RequestTarget {
+    raw: String,
+    path_segments: Vec<String>,
+    query_parameters: QueryParameters,
+}
+
+impl RequestTarget {
+    pub fn path(&self) -> &str {
+        match self.raw.split_once('?') {
+            Some((path, _)) => path,
+            None => &self.raw,
+        }
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.raw.split_once('?').map(|(_, query)| query)
+    }
+
+    pub fn path_segments(&self) -> &[String] {
+        &self.path_segments
+    }
+
+    pub fn query_parameters(&self) -> &QueryParameters {
+        &self.query_parameters
+    }
+}
+
+impl TryFrom<&str> for RequestTarget {
+    type Error = RequestTargetError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (path, query) = match value.split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (value, ""),
+        };
+
+        Ok(Self {
+            raw: value.to_string(),
+            path_segments: normalize_path(path)?,
+            query_parameters: QueryParameters::from(query),
+        })
+    }
+}
+
+fn normalize_path(path: &str) -> Result<Vec<String>, RequestTargetError> {
+    let raw_segments = path.split('/').collect_vec();
+    let last = raw_segments.len() - 1;
+    let mut path_segments = Vec::with_capacity(raw_segments.len());
+    for (index, raw_segment) in raw_segments.into_iter().enumerate() {
+        let segment = percent_decode(raw_segment, false);
+        match segment.as_str() {
+            ".." => return Err(RequestTargetError),
+            _ if segment.contains(['/', '\\']) => return Err(RequestTargetError),
+            "" if index == 0 => path_segments.push(segment),
+            "" | "." if index == last => path_segments.push(String::new()),
+            "" | "." => {}
+            _ => path_segments.push(segment),
+        }
+    }
+
+    Ok(path_segments)
+}
+
+#[derive(Debug)]
+pub struct RequestTargetError;
+
+pub struct QueryParameters(Vec<(String, String)>);
+
+impl QueryParameters {
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter()
+            .find(|(parameter_name, _)| *parameter_name == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn parse<T: FromStr>(&self, name: &str) -> Result<Option<T>, QueryParameterError> {
+        match self.get(name) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| QueryParameterError::new(name, value)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl From<&str> for QueryParameters {
+    fn from(value: &str) -> Self {
+        Self(
+            value
+                .split('&')
+                .filter(|parameter| !parameter.is_empty())
+                .map(|parameter| {
+                    let (name, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+
+                    (percent_decode(name, true), percent_decode(value, true))
+                })
+                .collect(),
+        )
+    }
+}
+
+pub fn percent_decode(value: &str, plus_as_space: bool) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = match bytes.get(index..index + 3) {
+            Some([b'%', high, low]) => hex_value(*high).zip(hex_value(*low)),
+            _ => None,
+        };
+        match (escaped, bytes[index]) {
+            (Some((high, low)), _) => {
+                decoded.push(high << 4 | low);
+                index += 3;
+            }
+            (None, b'+') if plus_as_space => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (None, byte) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+pub fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+pub fn has_path_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[derive(Debug)]
+pub struct QueryParameterError {
+    name: String,
+    value: String,
+}
+
+impl QueryParameterError {
+    fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Display for QueryParameterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid query parameter {}: {}", self.name, self.value)
+    }
+}
+
+impl Error for QueryParameterError {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn path_segments(request_target: &str) -> Vec<String> {
+        RequestTarget::try_from(request_target)
+            .unwrap()
+            .path_segments()
+            .to_vec()
+    }
+
+    #[test]
+    fn request_target_keeps_plain_paths() {
+        assert_eq!(path_segments("/"), ["", ""]);
+        assert_eq!(path_segments("/files/a.txt"), ["", "files", "a.txt"]);
+        assert_eq!(path_segments("/files/d/"), ["", "files", "d", ""]);
+        assert_eq!(path_segments("/echo/a%20b?x=1"), ["", "echo", "a b"]);
+    }
+
+    #[test]
+    fn request_target_collapses_dot_segments() {
+        assert_eq!(
+            path_segments("/files/./private/s.txt"),
+            ["", "files", "private", "s.txt"]
+        );
+        assert_eq!(
+            path_segments("/files/%2E/private/s.txt"),
+            ["", "files", "private", "s.txt"]
+        );
+        assert_eq!(path_segments("/files/d/."), ["", "files", "d", ""]);
+    }
+
+    #[test]
+    fn request_target_collapses_empty_segments() {
+        assert_eq!(
+            path_segments("/files//private/s.txt"),
+            ["", "files", "private", "s.txt"]
+        );
+        assert_eq!(path_segments("/files//"), ["", "files", ""]);
+        assert_eq!(path_segments("//"), ["", ""]);
+    }
+
+    #[test]
+    fn request_target_rejects_encoded_separators() {
+        for request_target in [
+            "/files/%2Fprivate/s.txt",
+            "/files/.%2Fprivate%2Fw.txt",
+            "/files/private%2fs.txt",
+            "/files/private%5Cs.txt",
+            "/files/private\\s.txt",
+        ] {
+            assert!(RequestTarget::try_from(request_target).is_err());
+        }
+    }
+
+    #[test]
+    fn request_target_rejects_parent_segments() {
+        for request_target in ["/files/../s.txt", "/files/%2E%2E/s.txt", "/files/.."] {
+            assert!(RequestTarget::try_from(request_target).is_err());
+        }
+    }
+
+    #[test]
+    fn has_path_prefix_matches_whole_segments() {
+        assert!(has_path_prefix("/files/private", "/files/private"));
+        assert!(has_path_prefix("/files/private/s.txt", "/files/private"));
+        assert!(has_path_prefix("/files/private/s.txt", "/files/private/"));
+        assert!(!has_path_prefix("/files/private2/s.txt", "/files/private"));
+        assert!(!has_path_prefix(
+            "/files/private-evil.txt",
+            "/files/private"
+        ));
+    }
+}
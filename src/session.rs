@@ -0,0 +1,320 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::Display,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+
+use crate::{
+    cookie::CookieOptions,
+    http_header::{CookieError, SetCookie},
+    http_request::HttpRequest,
+};
+
+pub const USER_KEY: &str = "user";
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub struct Session {
+    id: Option<String>,
+    values: HashMap<String, String>,
+    destroyed_id: Option<String>,
+    modified: bool,
+}
+
+impl Session {
+    fn new(id: Option<String>, values: HashMap<String, String>) -> Self {
+        Self {
+            id,
+            values,
+            destroyed_id: None,
+            modified: false,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.values.insert(key.to_string(), value.to_string());
+        self.modified = true;
+    }
+
+    pub fn destroy(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.destroyed_id = Some(id);
+        }
+        self.values.clear();
+        self.modified = true;
+    }
+
+    pub fn user(&self) -> Option<&str> {
+        self.get(USER_KEY)
+    }
+
+    pub fn log_in(&mut self, user_id: &str) {
+        self.destroy();
+        self.set(USER_KEY, user_id);
+    }
+
+    pub fn values(&self) -> &HashMap<String, String> {
+        &self.values
+    }
+}
+
+pub struct SessionRecord {
+    expires: SystemTime,
+    values: HashMap<String, String>,
+}
+
+pub enum SessionStore {
+    Memory(Mutex<HashMap<String, SessionRecord>>),
+    File(PathBuf),
+}
+
+impl SessionStore {
+    pub fn memory() -> Self {
+        Self::Memory(Mutex::new(HashMap::new()))
+    }
+
+    pub fn file(directory: PathBuf) -> Self {
+        Self::File(directory)
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<HashMap<String, String>>, SessionError> {
+        let now = SystemTime::now();
+        match self {
+            Self::Memory(session_records) => {
+                let mut session_records = session_records.lock().expect("Session store is usable");
+                match session_records.get(id) {
+                    Some(session_record) if session_record.expires > now => {
+                        Ok(Some(session_record.values.clone()))
+                    }
+                    Some(_) => {
+                        session_records.remove(id);
+
+                        Ok(None)
+                    }
+                    None => Ok(None),
+                }
+            }
+            Self::File(directory) => {
+                let session_path = directory.join(format!("{}.json", id));
+                let contents = match tokio::fs::read(&session_path).await {
+                    Ok(contents) => contents,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                    Err(e) => return Err(SessionError::Io(e)),
+                };
+                let session_record = serde_json::from_slice::<serde_json::Value>(&contents)
+                    .map_err(|e| SessionError::Malformed(e.to_string()))?;
+                let expires = session_record["expires"]
+                    .as_u64()
+                    .map(|expires| UNIX_EPOCH + Duration::from_secs(expires))
+                    .ok_or_else(|| SessionError::Malformed(String::from("missing expiry")))?;
+                if expires <= now {
+                    tokio::fs::remove_file(&session_path).await?;
+
+                    return Ok(None);
+                }
+                let values = serde_json::from_value(session_record["values"].clone())
+                    .map_err(|e| SessionError::Malformed(e.to_string()))?;
+
+                Ok(Some(values))
+            }
+        }
+    }
+
+    async fn put(
+        &self,
+        id: &str,
+        values: HashMap<String, String>,
+        expires: SystemTime,
+    ) -> Result<(), SessionError> {
+        match self {
+            Self::Memory(session_records) => {
+                let now = SystemTime::now();
+                let mut session_records = session_records.lock().expect("Session store is usable");
+                session_records.retain(|_, session_record| session_record.expires > now);
+                session_records.insert(id.to_string(), SessionRecord { expires, values });
+
+                Ok(())
+            }
+            Self::File(directory) => {
+                let expires = expires
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                let contents = json!({ "expires": expires, "values": values }).to_string();
+                let temp_path = directory.join(format!(".{}.json.tmp", id));
+                tokio::fs::write(&temp_path, contents).await?;
+                tokio::fs::rename(&temp_path, directory.join(format!("{}.json", id))).await?;
+
+                Ok(())
+            }
+        }
+    }
+
+    async fn remove(&self, id: &str) -> Result<(), SessionError> {
+        match self {
+            Self::Memory(session_records) => {
+                session_records
+                    .lock()
+                    .expect("Session store is usable")
+                    .remove(id);
+
+                Ok(())
+            }
+            Self::File(directory) => {
+                match tokio::fs::remove_file(directory.join(format!("{}.json", id))).await {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(SessionError::Io(e)),
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+pub struct SessionManager {
+    cookie_name: String,
+    keys: Vec<Vec<u8>>,
+    store: SessionStore,
+    ttl: Duration,
+}
+
+impl SessionManager {
+    pub fn new(
+        cookie_name: String,
+        keys: Vec<Vec<u8>>,
+        store: SessionStore,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            cookie_name,
+            keys,
+            store,
+            ttl,
+        }
+    }
+
+    pub async fn load(&self, http_request: &HttpRequest) -> Session {
+        let id = http_request
+            .cookies()
+            .get(self.cookie_name.as_str())
+            .and_then(|cookie_value| self.verify(cookie_value));
+        let Some(id) = id else {
+            return Session::new(None, HashMap::new());
+        };
+
+        match self.store.get(&id).await {
+            Ok(Some(values)) => Session::new(Some(id), values),
+            Ok(None) => Session::new(None, HashMap::new()),
+            Err(e) => {
+                println!("error: {e}");
+
+                Session::new(None, HashMap::new())
+            }
+        }
+    }
+
+    pub async fn save(
+        &self,
+        session: Session,
+        cookie_options: &CookieOptions,
+    ) -> Result<Option<SetCookie>, SessionError> {
+        if !session.modified {
+            return Ok(None);
+        }
+        if let Some(destroyed_id) = &session.destroyed_id {
+            self.store.remove(destroyed_id).await?;
+        }
+        if session.values.is_empty() {
+            if let Some(id) = &session.id {
+                self.store.remove(id).await?;
+            }
+
+            return match session.destroyed_id.or(session.id) {
+                Some(_) => Ok(Some(cookie_options.expire_cookie(&self.cookie_name)?)),
+                None => Ok(None),
+            };
+        }
+
+        let id = session
+            .id
+            .unwrap_or_else(|| URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>()));
+        self.store
+            .put(&id, session.values, SystemTime::now() + self.ttl)
+            .await?;
+        let max_age = i64::try_from(self.ttl.as_secs()).unwrap_or(i64::MAX);
+
+        Ok(Some(
+            cookie_options
+                .set_cookie(&self.cookie_name, &self.sign(&id))?
+                .with_max_age(max_age),
+        ))
+    }
+
+    fn sign(&self, id: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(&self.keys[0]).expect("HMAC accepts any key size");
+        mac.update(id.as_bytes());
+
+        format!(
+            "{}.{}",
+            id,
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+        )
+    }
+
+    fn verify(&self, cookie_value: &str) -> Option<String> {
+        let (id, signature) = cookie_value.split_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+
+        self.keys
+            .iter()
+            .any(|key| {
+                let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key size");
+                mac.update(id.as_bytes());
+
+                mac.verify_slice(&signature).is_ok()
+            })
+            .then(|| id.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(std::io::Error),
+    Cookie(CookieError),
+    Malformed(String),
+}
+
+impl From<std::io::Error> for SessionError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<CookieError> for SessionError {
+    fn from(value: CookieError) -> Self {
+        Self::Cookie(value)
+    }
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(io_err) => write!(f, "{}", io_err),
+            Self::Cookie(cookie_err) => write!(f, "{}", cookie_err),
+            Self::Malformed(reason) => write!(f, "Invalid session record: {}", reason),
+        }
+    }
+}
+
+impl Error for SessionError {}
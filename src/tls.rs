@@ -0,0 +1,241 @@
+use std::{error::Error, fmt::Display, fs::File, io::BufReader, path::Path, sync::Arc};
+
+use tokio_rustls::{
+    rustls::{
+        server::{
+            AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient, NoClientAuth,
+        },
+        Certificate, PrivateKey, RootCertStore, ServerConfig,
+    },
+    TlsAcceptor,
+};
+use x509_parser::{certificate::X509Certificate, extensions::GeneralName, prelude::FromDer};
+
+use crate::{http_request::HttpRequest, request_target::has_path_prefix};
+
+#[derive(Clone, Copy)]
+pub enum ClientAuth {
+    Optional,
+    Required,
+}
+
+impl TryFrom<&str> for ClientAuth {
+    type Error = ClientAuthError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "optional" => Ok(Self::Optional),
+            "required" => Ok(Self::Required),
+            _ => Err(ClientAuthError::new(value)),
+        }
+    }
+}
+
+pub fn acceptor(
+    cert: &Path,
+    key: &Path,
+    client_ca: Option<&Path>,
+    client_auth: ClientAuth,
+) -> anyhow::Result<TlsAcceptor> {
+    let certs = read_certs(cert)?;
+    let key = read_private_key(key)?;
+
+    let client_cert_verifier = match client_ca {
+        Some(client_ca) => {
+            let mut roots = RootCertStore::empty();
+            for ca_cert in read_certs(client_ca)? {
+                roots.add(&ca_cert)?;
+            }
+
+            match client_auth {
+                ClientAuth::Optional => AllowAnyAnonymousOrAuthenticatedClient::new(roots).boxed(),
+                ClientAuth::Required => AllowAnyAuthenticatedClient::new(roots).boxed(),
+            }
+        }
+        None => NoClientAuth::boxed(),
+    };
+
+    let server_config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_client_cert_verifier(client_cert_verifier)
+        .with_single_cert(certs, key)?;
+
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+fn read_certs(path: &Path) -> anyhow::Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn read_private_key(path: &Path) -> anyhow::Result<PrivateKey> {
+    let mut reader = BufReader::new(File::open(path)?);
+    while let Some(item) = rustls_pemfile::read_one(&mut reader)? {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+            _ => {}
+        }
+    }
+
+    anyhow::bail!("No private key found in {}", path.display())
+}
+
+#[derive(Clone)]
+pub struct ClientCertificate {
+    subject: String,
+    subject_alt_names: Vec<String>,
+}
+
+impl ClientCertificate {
+    pub fn from_der(der: &[u8]) -> Option<Self> {
+        let (_, certificate) = X509Certificate::from_der(der).ok()?;
+        let subject = certificate.subject().to_string();
+        let subject_alt_names = match certificate.subject_alternative_name() {
+            Ok(Some(subject_alternative_name)) => subject_alternative_name
+                .value
+                .general_names
+                .iter()
+                .filter_map(|general_name| match general_name {
+                    GeneralName::DNSName(name)
+                    | GeneralName::RFC822Name(name)
+                    | GeneralName::URI(name) => Some(name.to_string()),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        Some(Self {
+            subject,
+            subject_alt_names,
+        })
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn subject_alt_names(&self) -> &[String] {
+        &self.subject_alt_names
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.subject() == name
+            || self.subject() == format!("CN={}", name)
+            || self
+                .subject_alt_names()
+                .iter()
+                .any(|subject_alt_name| subject_alt_name == name)
+    }
+}
+
+pub struct ClientCertificatePolicy {
+    paths: Vec<String>,
+    allowed_names: Vec<String>,
+}
+
+impl ClientCertificatePolicy {
+    pub fn new(paths: Vec<String>, allowed_names: Vec<String>) -> Self {
+        Self {
+            paths,
+            allowed_names,
+        }
+    }
+
+    pub fn allows(&self, http_request: &HttpRequest) -> bool {
+        let decoded_path = http_request.decoded_path();
+        let requires_client_certificate = self
+            .paths
+            .iter()
+            .any(|path| has_path_prefix(&decoded_path, path));
+        if !requires_client_certificate {
+            return true;
+        }
+
+        match http_request.client_certificate() {
+            Some(client_certificate) => {
+                self.allowed_names.is_empty()
+                    || self
+                        .allowed_names
+                        .iter()
+                        .any(|name| client_certificate.matches(name))
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClientAuthError {
+    client_auth: String,
+}
+
+impl ClientAuthError {
+    fn new(client_auth: &str) -> Self {
+        Self {
+            client_auth: client_auth.to_string(),
+        }
+    }
+}
+
+impl Display for ClientAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid client auth mode: {}", self.client_auth)
+    }
+}
+
+impl Error for ClientAuthError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn allows(raw: &str, client_certificate: Option<ClientCertificate>) -> bool {
+        let client_certificate_policy = ClientCertificatePolicy::new(
+            vec![String::from("/files/private")],
+            vec![String::from("service")],
+        );
+        let http_request = HttpRequest::from_reader(&mut raw.as_bytes(), client_certificate)
+            .await
+            .unwrap()
+            .unwrap();
+
+        client_certificate_policy.allows(&http_request)
+    }
+
+    fn client_certificate(subject: &str) -> ClientCertificate {
+        ClientCertificate {
+            subject: subject.to_string(),
+            subject_alt_names: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn client_certificate_policy_protects_its_paths() {
+        let raw = "GET /files/private/s.txt HTTP/1.1\r\nHost: x\r\n\r\n";
+
+        assert!(!allows(raw, None).await);
+        assert!(!allows(raw, Some(client_certificate("CN=other"))).await);
+        assert!(allows(raw, Some(client_certificate("CN=service"))).await);
+        assert!(allows("GET /files/public.txt HTTP/1.1\r\nHost: x\r\n\r\n", None).await);
+        assert!(allows("GET /files/private2 HTTP/1.1\r\nHost: x\r\n\r\n", None).await);
+    }
+
+    #[tokio::test]
+    async fn client_certificate_policy_applies_to_dot_segments() {
+        let raw = "GET /files/./private/s.txt HTTP/1.1\r\nHost: x\r\n\r\n";
+
+        assert!(!allows(raw, None).await);
+    }
+
+    #[tokio::test]
+    async fn client_certificate_policy_applies_to_empty_segments() {
+        let raw = "GET /files//private/s.txt HTTP/1.1\r\nHost: x\r\n\r\n";
+
+        assert!(!allows(raw, None).await);
+    }
+}
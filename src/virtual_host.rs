@@ -0,0 +1,188 @@
+use std::{error::Error, fmt::Display, path::PathBuf, sync::Arc};
+
+use crate::directory_listing::DirectoryListing;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Route {
+    Root,
+    Echo,
+    UserAgent,
+    Session,
+    Upload,
+    Files,
+}
+
+impl TryFrom<&str> for Route {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "root" => Ok(Self::Root),
+            "echo" => Ok(Self::Echo),
+            "user-agent" => Ok(Self::UserAgent),
+            "session" => Ok(Self::Session),
+            "upload" => Ok(Self::Upload),
+            "files" => Ok(Self::Files),
+            _ => Err(()),
+        }
+    }
+}
+
+pub struct VirtualHost {
+    name: String,
+    directory: PathBuf,
+    routes: Option<Vec<Route>>,
+    directory_listing: Option<DirectoryListing>,
+    spa_fallback: Option<String>,
+}
+
+impl VirtualHost {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn directory(&self) -> &PathBuf {
+        &self.directory
+    }
+
+    pub fn directory_listing(&self) -> Option<&DirectoryListing> {
+        self.directory_listing.as_ref()
+    }
+
+    pub fn spa_fallback(&self) -> Option<&str> {
+        self.spa_fallback.as_deref()
+    }
+
+    pub fn allows(&self, route: Route) -> bool {
+        match &self.routes {
+            Some(routes) => routes.contains(&route),
+            None => true,
+        }
+    }
+
+    fn wildcard_suffix(&self) -> Option<&str> {
+        self.name.strip_prefix('*')
+    }
+}
+
+impl TryFrom<&str> for VirtualHost {
+    type Error = VirtualHostError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut options = value.split(';');
+        let (name, directory) = options
+            .next()
+            .unwrap_or_default()
+            .split_once('=')
+            .filter(|(name, directory)| {
+                let hostname = name.strip_prefix("*.").unwrap_or(name);
+
+                !hostname.is_empty() && !hostname.contains('*') && !directory.is_empty()
+            })
+            .ok_or_else(|| VirtualHostError::new(value))?;
+
+        let mut virtual_host = Self {
+            name: name.to_ascii_lowercase(),
+            directory: PathBuf::from(directory),
+            routes: None,
+            directory_listing: None,
+            spa_fallback: None,
+        };
+        for option in options {
+            match option.split_once('=') {
+                Some(("routes", routes)) => {
+                    let routes = routes
+                        .split(',')
+                        .map(Route::try_from)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| VirtualHostError::new(value))?;
+                    virtual_host.routes = Some(routes);
+                }
+                Some(("listing", "on")) => {
+                    virtual_host.directory_listing = Some(DirectoryListing::new(false));
+                }
+                Some(("listing", "hidden")) => {
+                    virtual_host.directory_listing = Some(DirectoryListing::new(true));
+                }
+                Some(("spa-fallback", spa_fallback)) if !spa_fallback.is_empty() => {
+                    virtual_host.spa_fallback = Some(spa_fallback.to_string());
+                }
+                _ => return Err(VirtualHostError::new(value)),
+            }
+        }
+
+        Ok(virtual_host)
+    }
+}
+
+pub struct VirtualHosts {
+    virtual_hosts: Vec<Arc<VirtualHost>>,
+    default_host: Option<Arc<VirtualHost>>,
+}
+
+impl VirtualHosts {
+    pub fn new(
+        virtual_hosts: Vec<VirtualHost>,
+        default_host: Option<&str>,
+    ) -> Result<Self, VirtualHostError> {
+        let virtual_hosts = virtual_hosts.into_iter().map(Arc::new).collect::<Vec<_>>();
+        let default_host = match default_host {
+            Some(default_host) => Some(
+                virtual_hosts
+                    .iter()
+                    .find(|virtual_host| virtual_host.name() == default_host)
+                    .cloned()
+                    .ok_or_else(|| VirtualHostError::new(default_host))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            virtual_hosts,
+            default_host,
+        })
+    }
+
+    pub fn resolve(&self, hostname: Option<&str>) -> Option<Arc<VirtualHost>> {
+        let virtual_host = hostname.and_then(|hostname| {
+            let exact = self
+                .virtual_hosts
+                .iter()
+                .find(|virtual_host| virtual_host.name() == hostname);
+
+            exact.or_else(|| {
+                self.virtual_hosts
+                    .iter()
+                    .filter(|virtual_host| {
+                        virtual_host
+                            .wildcard_suffix()
+                            .is_some_and(|suffix| hostname.ends_with(suffix))
+                    })
+                    .max_by_key(|virtual_host| virtual_host.name().len())
+            })
+        });
+
+        virtual_host.or(self.default_host.as_ref()).cloned()
+    }
+}
+
+#[derive(Debug)]
+pub struct VirtualHostError {
+    virtual_host: String,
+}
+
+impl VirtualHostError {
+    fn new(virtual_host: &str) -> Self {
+        Self {
+            virtual_host: virtual_host.to_string(),
+        }
+    }
+}
+
+impl Display for VirtualHostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid virtual host: {}", self.virtual_host)
+    }
+}
+
+impl Error for VirtualHostError {}